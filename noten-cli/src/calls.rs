@@ -0,0 +1,212 @@
+use std::collections::BTreeMap;
+
+use casper_types::{Key, PublicKey, U256};
+use casper_types::bytesrepr::Bytes;
+use cep47::TokenId;
+use noten_client::NotenCall;
+use noten_client::appointment::Appointment;
+use noten_client::exam::Exam;
+use noten_client::origin::Recognition;
+use noten_client::scale::GradingScale;
+
+/// Entry point arguments given on the command line as `name=value`.
+///
+/// Keys are formatted keys (`account-hash-…`, `hash-…`), lists are comma separated and
+/// byte strings are hex.
+pub struct CallArgs(BTreeMap<String, String>);
+
+impl CallArgs {
+    pub fn parse(args: &[String]) -> Result<Self, String> {
+        let mut values = BTreeMap::new();
+        for arg in args {
+            let (name, value) = arg.split_once('=').ok_or_else(|| format!("expected name=value, got '{}'", arg))?;
+            values.insert(name.to_string(), value.to_string());
+        }
+        Ok(CallArgs(values))
+    }
+
+    fn raw(&self, name: &str) -> Result<&str, String> {
+        self.0.get(name).map(String::as_str).ok_or_else(|| format!("missing argument '{}'", name))
+    }
+
+    fn string(&self, name: &str) -> Result<String, String> {
+        self.raw(name).map(str::to_string)
+    }
+
+    fn strings(&self, name: &str) -> Result<Vec<String>, String> {
+        Ok(list(self.raw(name)?).map(str::to_string).collect())
+    }
+
+    fn key(&self, name: &str) -> Result<Key, String> {
+        let value = self.raw(name)?;
+        Key::from_formatted_str(value).map_err(|_| format!("'{}' is not a formatted key", value))
+    }
+
+    fn u32(&self, name: &str) -> Result<u32, String> {
+        let value = self.raw(name)?;
+        value.parse().map_err(|_| format!("'{}' is not a u32", value))
+    }
+
+    fn u64(&self, name: &str) -> Result<u64, String> {
+        let value = self.raw(name)?;
+        value.parse().map_err(|_| format!("'{}' is not a u64", value))
+    }
+
+    fn token_id(&self, name: &str) -> Result<TokenId, String> {
+        token_id(self.raw(name)?)
+    }
+
+    fn token_ids(&self, name: &str) -> Result<Vec<TokenId>, String> {
+        list(self.raw(name)?).map(token_id).collect()
+    }
+
+    fn public_keys(&self, name: &str) -> Result<Vec<PublicKey>, String> {
+        list(self.raw(name)?)
+            .map(|value| PublicKey::from_hex(value).map_err(|_| format!("'{}' is not a public key", value)))
+            .collect()
+    }
+
+    fn bytes(&self, name: &str) -> Result<Vec<u8>, String> {
+        let value = self.raw(name)?;
+        hex::decode(value).map_err(|_| format!("'{}' is not hex", value))
+    }
+
+    fn hash(&self, name: &str) -> Result<[u8; 32], String> {
+        let bytes = self.bytes(name)?;
+        let mut hash = [0u8; 32];
+        if bytes.len() != hash.len() {
+            return Err(format!("'{}' has to be 32 bytes", name));
+        }
+        hash.copy_from_slice(&bytes);
+        Ok(hash)
+    }
+}
+
+fn list(value: &str) -> impl Iterator<Item = &str> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty())
+}
+
+fn token_id(value: &str) -> Result<TokenId, String> {
+    U256::from_dec_str(value).map_err(|_| format!("'{}' is not a token id", value))
+}
+
+/// Builds the call of `entry_point` from its command line arguments.
+pub fn build(entry_point: &str, args: &CallArgs) -> Result<NotenCall, String> {
+    let call = match entry_point {
+        "name" => NotenCall::name(),
+        "symbol" => NotenCall::symbol(),
+        "meta" => NotenCall::meta(),
+        "token_meta" => NotenCall::token_meta(args.token_id("token_id")?),
+        "migrate" => NotenCall::migrate(args.token_ids("token_ids")?),
+        "import_legacy_grade" => NotenCall::import_legacy_grade(
+            args.key("student")?,
+            &args.string("subject")?,
+            args.u32("year")?,
+            &args.string("type")?,
+            args.u32("grade")?,
+            args.key("source_package")?,
+        ),
+        "grade" => NotenCall::grade(
+            args.key("student")?,
+            &args.string("subject")?,
+            args.u32("year")?,
+            &args.string("type")?,
+            args.u32("grade")?,
+        ),
+        "update_grade" => NotenCall::update_grade(args.key("student")?, args.u32("grade")?, args.token_id("token_id")?),
+        "grade_record" => NotenCall::grade_record(args.token_id("token_id")?),
+        "register_exam" => NotenCall::register_exam(&Exam {
+            subject: args.string("subject")?,
+            class: args.string("class")?,
+            year: args.u32("year")?,
+            date: args.u64("date")?,
+            exam_type: args.string("type")?,
+        }),
+        "grade_exam" => NotenCall::grade_exam(args.key("student")?, args.u32("exam_id")?, args.u32("grade")?),
+        "exam" => NotenCall::exam(args.u32("exam_id")?),
+        "exam_summary" => NotenCall::exam_summary(args.u32("exam_id")?),
+        "grading_scale" => NotenCall::grading_scale(),
+        "set_grading_scale" => NotenCall::set_grading_scale(GradingScale {
+            min: args.u32("min")?,
+            max: args.u32("max")?,
+            step: args.u32("step")?,
+        }),
+        "grades_page" => NotenCall::grades_page(args.u64("offset")?, args.u64("limit")?),
+        "grades_since" => NotenCall::grades_since(args.u64("event_index")?, args.u64("limit")?),
+        "staff_events_since" => NotenCall::staff_events_since(args.u64("event_index")?, args.u64("limit")?),
+        "add_teacher" => NotenCall::add_teacher(args.key("teacher")?),
+        "remove_teacher" => NotenCall::remove_teacher(args.key("teacher")?),
+        "appoint_teacher" => NotenCall::appoint_teacher(args.key("teacher")?, &Appointment {
+            start: args.u64("start")?,
+            end: args.u64("end")?,
+            subjects: args.strings("subjects")?,
+            classes: args.strings("classes")?,
+        }),
+        "appointment" => NotenCall::appointment(args.key("teacher")?),
+        "set_student_class" => NotenCall::set_student_class(args.key("student")?, &args.string("class")?),
+        "student_class" => NotenCall::student_class(args.key("student")?),
+        "set_grade_payload" => NotenCall::set_grade_payload(
+            args.token_id("token_id")?,
+            args.public_keys("recipients")?,
+            Bytes::from(args.bytes("payload")?),
+        ),
+        "grade_payload" => NotenCall::grade_payload(args.token_id("token_id")?),
+        "link_pseudonym" => NotenCall::link_pseudonym(args.key("pseudonym")?, args.hash("commitment")?),
+        "claim_pseudonym" => NotenCall::claim_pseudonym(args.key("pseudonym")?, &args.string("nonce")?),
+        "pseudonym_account" => NotenCall::pseudonym_account(args.key("pseudonym")?),
+        "add_guardian" => NotenCall::add_guardian(args.key("student")?, args.key("guardian")?),
+        "remove_guardian" => NotenCall::remove_guardian(args.key("student")?, args.key("guardian")?),
+        "guardians" => NotenCall::guardians(args.key("student")?),
+        "acknowledge_grade" => NotenCall::acknowledge_grade(args.token_id("token_id")?),
+        "acknowledgement" => NotenCall::acknowledgement(args.token_id("token_id")?, args.key("guardian")?),
+        "file_appeal" => NotenCall::file_appeal(args.token_id("token_id")?, &args.string("reason")?),
+        "review_appeal" => NotenCall::review_appeal(args.token_id("token_id")?),
+        "uphold_appeal" => NotenCall::uphold_appeal(args.token_id("token_id")?, args.u32("grade")?),
+        "reject_appeal" => NotenCall::reject_appeal(args.token_id("token_id")?),
+        "appeal" => NotenCall::appeal(args.token_id("token_id")?),
+        "add_reviewer" => NotenCall::add_reviewer(args.key("reviewer")?),
+        "remove_reviewer" => NotenCall::remove_reviewer(args.key("reviewer")?),
+        "revoke_grade" => NotenCall::revoke_grade(args.token_id("token_id")?),
+        "propose_remove_teacher" => NotenCall::propose_remove_teacher(args.key("teacher")?, args.u64("deadline")?),
+        "propose_revoke_grade" => NotenCall::propose_revoke_grade(args.token_id("token_id")?, args.u64("deadline")?),
+        "propose_approval_threshold" => NotenCall::propose_approval_threshold(args.u32("threshold")?, args.u64("deadline")?),
+        "propose_add_teacher" => NotenCall::propose_add_teacher(args.key("teacher")?, args.u64("deadline")?),
+        "propose_appoint_teacher" => NotenCall::propose_appoint_teacher(args.key("teacher")?, &Appointment {
+            start: args.u64("start")?,
+            end: args.u64("end")?,
+            subjects: args.strings("subjects")?,
+            classes: args.strings("classes")?,
+        }, args.u64("deadline")?),
+        "propose_unpause" => NotenCall::propose_unpause(args.u64("deadline")?),
+        "propose_reopen_term" => NotenCall::propose_reopen_term(args.u32("year")?, args.u64("deadline")?),
+        "approve_proposal" => NotenCall::approve_proposal(args.u32("proposal_id")?),
+        "proposal" => NotenCall::proposal(args.u32("proposal_id")?),
+        "pause" => NotenCall::pause(),
+        "rollover_year" => NotenCall::rollover_year(args.u32("year")?, &args.string("archive")?, args.u32("limit")?),
+        "set_final_class_level" => NotenCall::set_final_class_level(args.u32("level")?),
+        "reopen_term" => NotenCall::reopen_term(args.u32("year")?),
+        "average" => NotenCall::average(args.key("student")?, &args.string("subject")?, args.u32("year")?),
+        "final_average" => NotenCall::final_average(args.key("student")?, &args.string("subject")?, args.u32("year")?),
+        "graduation" => NotenCall::graduation(args.key("student")?),
+        "year_archive" => NotenCall::year_archive(args.u32("year")?),
+        "issue_transfer" => NotenCall::issue_transfer(args.key("student")?, args.token_ids("token_ids")?, args.key("destination")?),
+        "transfer_record" => NotenCall::transfer_record(args.u32("transfer_id")?),
+        "set_registry" => NotenCall::set_registry(args.key("registry")?),
+        "import_transfer" => NotenCall::import_transfer(args.key("school")?, args.u32("transfer_id")?, args.key("student")?),
+        "recognise_grade" => NotenCall::recognise_grade(
+            args.key("student")?,
+            &args.string("subject")?,
+            args.u32("year")?,
+            args.u32("grade")?,
+            &Recognition {
+                institution: args.string("institution")?,
+                scale: args.string("original_scale")?,
+                value: args.string("original_value")?,
+                rule: args.string("conversion_rule")?,
+            },
+        ),
+        "grade_origin" => NotenCall::grade_origin(args.token_id("token_id")?),
+        _ => return Err(format!("unknown entry point '{}'", entry_point)),
+    };
+    Ok(call)
+}
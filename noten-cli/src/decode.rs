@@ -0,0 +1,180 @@
+use casper_types::bytesrepr::FromBytes;
+use casper_types::{CLType, CLValue, Key};
+use cep47::Meta;
+use noten_client::appeal::Appeal;
+use noten_client::appointment::Appointment;
+use noten_client::decode::dictionary_value;
+use noten_client::exam::{Exam, ExamStats};
+use noten_client::origin::GradeOrigin;
+use noten_client::proposal::{Action, Proposal};
+use noten_client::record::GradeRecord;
+use noten_client::scale::GradingScale;
+use noten_client::transfer::Transfer;
+use serde_json::Value;
+
+use crate::table::Table;
+
+pub const KINDS: [&str; 9] = [
+    "grade-record",
+    "meta",
+    "appeal",
+    "proposal",
+    "appointment",
+    "exam",
+    "exam-stats",
+    "transfer",
+    "origin",
+];
+
+/// Finds the stored value's bytes in the output of `casper-client query-global-state` or
+/// `get-dictionary-item`.
+pub fn stored_bytes(json: &Value) -> Result<Vec<u8>, String> {
+    let value = json.get("result").unwrap_or(json);
+    let value = value.get("stored_value").unwrap_or(value);
+    let bytes = value
+        .pointer("/CLValue/bytes")
+        .and_then(Value::as_str)
+        .ok_or("no CLValue bytes in the query result")?;
+    hex::decode(bytes).map_err(|_| "CLValue bytes are not hex".to_string())
+}
+
+fn value<T: FromBytes>(bytes: Vec<u8>) -> Result<T, String> {
+    dictionary_value(&CLValue::from_components(CLType::Any, bytes))
+        .map_err(|error| format!("could not decode the value: {}", error))?
+        .ok_or_else(|| "no value stored".to_string())
+}
+
+/// Decodes a dictionary item of the given kind into a table. Exam statistics are summarised
+/// on `scale`, which has to be the contract's grading scale.
+pub fn table(kind: &str, bytes: Vec<u8>, scale: &GradingScale) -> Result<Table, String> {
+    let mut table = Table::default();
+    match kind {
+        "grade-record" => grade_record(&mut table, &value(bytes)?),
+        "meta" => {
+            let meta: Meta = value(bytes)?;
+            for (field, value) in meta {
+                table.row(&field, value);
+            }
+        }
+        "appeal" => {
+            let appeal: Appeal = value(bytes)?;
+            table
+                .row("appellant", key(&appeal.appellant))
+                .row("reason", appeal.reason)
+                .row("status", format!("{:?}", appeal.status));
+        }
+        "proposal" => {
+            let proposal: Proposal = value(bytes)?;
+            table
+                .row("action", action(&proposal.action))
+                .row("proposer", key(&proposal.proposer))
+                .row("deadline", proposal.deadline)
+                .row("approvals", keys(&proposal.approvals))
+                .row("executed", proposal.executed);
+        }
+        "appointment" => {
+            let appointment: Appointment = value(bytes)?;
+            table
+                .row("start", appointment.start)
+                .row("end", appointment.end)
+                .row("subjects", appointment.subjects.join(", "))
+                .row("classes", appointment.classes.join(", "));
+        }
+        "exam" => {
+            let exam: Exam = value(bytes)?;
+            table
+                .row("subject", exam.subject)
+                .row("class", exam.class)
+                .row("year", exam.year)
+                .row("date", exam.date)
+                .row("type", exam.exam_type);
+        }
+        "exam-stats" => {
+            let stats: ExamStats = value(bytes)?;
+            let summary = stats.summary(scale);
+            table
+                .row("count", summary.count)
+                .optional("mean", summary.mean)
+                .optional("median", summary.median)
+                .optional("min", summary.min)
+                .optional("max", summary.max)
+                .row("histogram", list(&summary.histogram));
+        }
+        "transfer" => {
+            let transfer: Transfer = value(bytes)?;
+            table
+                .row("student", key(&transfer.student))
+                .row("destination", key(&transfer.destination))
+                .row("issued_by", key(&transfer.issued_by))
+                .row("issued_at", transfer.issued_at);
+            for (token_id, record) in transfer.grades {
+                table.row(&format!("grade {}", token_id), format!(
+                    "{} {} {} {}",
+                    record.subject, record.year, record.grade_type, record.grade
+                ));
+            }
+        }
+        "origin" => match value(bytes)? {
+            GradeOrigin::Transfer { school, transfer_id, token_id } => {
+                table
+                    .row("origin", "transfer")
+                    .row("school", key(&school))
+                    .row("transfer_id", transfer_id)
+                    .row("token_id", token_id);
+            }
+            GradeOrigin::Recognition(recognition) => {
+                table
+                    .row("origin", "recognition")
+                    .row("institution", recognition.institution)
+                    .row("original_scale", recognition.scale)
+                    .row("original_value", recognition.value)
+                    .row("conversion_rule", recognition.rule);
+            }
+        },
+        _ => return Err(format!("unknown kind '{}', expected one of {}", kind, KINDS.join(", "))),
+    }
+    Ok(table)
+}
+
+fn grade_record(table: &mut Table, record: &GradeRecord) {
+    table
+        .row("student", key(&record.student))
+        .row("subject", &record.subject)
+        .row("year", record.year)
+        .row("type", &record.grade_type)
+        .row("grade", record.grade)
+        .row("teacher", key(&record.teacher))
+        .row("issued_at", record.issued_at)
+        .row("contract_version", record.contract_version);
+}
+
+fn action(action: &Action) -> String {
+    match action {
+        Action::RemoveTeacher(teacher) => format!("remove teacher {}", key(teacher)),
+        Action::RevokeGrade(token_id) => format!("revoke grade {}", token_id),
+        Action::SetApprovalThreshold(threshold) => format!("set approval threshold to {}", threshold),
+        Action::Unpause => "unpause".to_string(),
+        Action::ReopenTerm(year) => format!("reopen term {}", year),
+        Action::AddTeacher(teacher) => format!("add teacher {}", key(teacher)),
+        Action::AppointTeacher(teacher, appointment) => format!(
+            "appoint teacher {} from {} to {} for subjects [{}] and classes [{}]",
+            key(teacher),
+            appointment.start,
+            appointment.end,
+            appointment.subjects.join(", "),
+            appointment.classes.join(", "),
+        ),
+    }
+}
+
+fn key(key: &Key) -> String {
+    key.to_formatted_string()
+}
+
+fn keys(keys: &[Key]) -> String {
+    keys.iter().map(key).collect::<Vec<_>>().join(", ")
+}
+
+fn list(values: &[u32]) -> String {
+    values.iter().map(u32::to_string).collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,228 @@
+//! Prepares and signs deploys for the noten contract without a node, and decodes the values
+//! `casper-client` queries return. `run` is the command line, which the binary calls with its
+//! arguments and stdout.
+
+mod calls;
+mod decode;
+mod table;
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::{env, fs, process};
+
+use casper_client::{DeployStrParams, PaymentStrParams, SessionStrParams};
+use casper_types::bytesrepr::ToBytes;
+use noten_client::NotenCall;
+use noten_client::import::{self, Students};
+use noten_client::scale::GradingScale;
+
+use calls::CallArgs;
+
+const USAGE: &str = "\
+usage:
+  noten-cli make-deploy --secret-key <pem> --chain-name <name> --package <hash>
+                        [--payment <motes>] [--ttl <ttl>] [--output <file>] [--force]
+                        <entry_point> [name=value ...]
+  noten-cli import-grades --secret-key <pem> --chain-name <name> --package <hash>
+                          --year <year> (--roster <csv> | --salt <salt>) --output-dir <dir>
+                          --scale <min>,<max>,<step> [--from-line <line>] [--payment <motes>]
+                          [--ttl <ttl>] [--force] <grades.csv>
+  noten-cli sign-deploy --secret-key <pem> --input <file> [--output <file>] [--force]
+  noten-cli decode [--scale <min>,<max>,<step>] <kind> <query.json>
+  noten-cli kinds
+
+Keys are given formatted (account-hash-..., hash-...), lists comma separated and bytes as hex.
+Without --output the deploy is printed. import-grades checks every row against the contract's
+--scale and writes one grade deploy per row, and nothing at all if a row can't be imported. If
+writing a deploy fails, it reports the lines written so far; --from-line resumes with the line
+that failed, or one whose deploy was refused.";
+
+const DEFAULT_PAYMENT: &str = "5000000000";
+const DEFAULT_TTL: &str = "30min";
+
+/// Command line flags given as `--name value` or a bare `--name`, and the remaining arguments.
+struct Options {
+    flags: BTreeMap<String, Option<String>>,
+    positional: Vec<String>,
+}
+
+impl Options {
+    const SWITCHES: [&'static str; 1] = ["force"];
+
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut flags = BTreeMap::new();
+        let mut positional = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) if Self::SWITCHES.contains(&name) => {
+                    flags.insert(name.to_string(), None);
+                }
+                Some(name) => {
+                    let value = args.next().ok_or_else(|| format!("--{} needs a value", name))?;
+                    flags.insert(name.to_string(), Some(value));
+                }
+                None => positional.push(arg),
+            }
+        }
+        Ok(Options { flags, positional })
+    }
+
+    fn flag(&self, name: &str) -> Result<&str, String> {
+        self.optional(name).ok_or_else(|| format!("missing --{}", name))
+    }
+
+    fn optional(&self, name: &str) -> Option<&str> {
+        self.flags.get(name).and_then(|value| value.as_deref())
+    }
+
+    fn switch(&self, name: &str) -> bool {
+        self.flags.contains_key(name)
+    }
+}
+
+/// Runs the command in `args`, the command line without the program name, writing what it
+/// prints to `out`. Deploys printed for lack of `--output` go to stdout regardless.
+pub fn run(mut args: impl Iterator<Item = String>, out: &mut impl Write) -> Result<(), String> {
+    let command = args.next().unwrap_or_default();
+    let options = Options::parse(args)?;
+    match command.as_str() {
+        "make-deploy" => make_deploy(&options),
+        "import-grades" => import_grades(&options, out),
+        "sign-deploy" => sign_deploy(&options),
+        "decode" => decode(&options, out),
+        "kinds" => writeln!(out, "{}", decode::KINDS.join("\n")).map_err(|error| error.to_string()),
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+/// Writes an entry point call as a signed deploy.
+fn make_deploy(options: &Options) -> Result<(), String> {
+    let (entry_point, args) = options.positional.split_first().ok_or(USAGE)?;
+    let call = calls::build(entry_point, &CallArgs::parse(args)?)?;
+    write_deploy(options, &call, options.optional("output").unwrap_or(""))
+}
+
+/// Checks every row of a grades CSV and only then writes a deploy per row, named after its line,
+/// starting at `--from-line` if given.
+fn import_grades(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let path = match options.positional.as_slice() {
+        [path] => path,
+        _ => return Err(USAGE.to_string()),
+    };
+    let year = options.flag("year")?.parse::<u32>().map_err(|_| "--year has to be a year".to_string())?;
+    let students = match (options.optional("roster"), options.optional("salt")) {
+        (Some(roster), None) => Students::Roster(import::read_roster(&read(roster)?).map_err(|errors| report(roster, &errors))?),
+        (None, Some(salt)) => Students::Pseudonyms { salt: salt.to_string() },
+        _ => return Err("expected either --roster or --salt".to_string()),
+    };
+    // Rows are checked against the scale before any deploy is written, so it can't be guessed.
+    let scale = parse_scale(options.flag("scale")?)?;
+    let rows = import::read_grades(&read(path)?, &students, &scale).map_err(|errors| report(path, &errors))?;
+    let from_line = match options.optional("from-line") {
+        Some(line) => line.parse::<usize>().map_err(|_| format!("--from-line has to be a line number, got '{}'", line))?,
+        None => 0,
+    };
+
+    let output_dir = Path::new(options.flag("output-dir")?);
+    fs::create_dir_all(output_dir).map_err(|error| error.to_string())?;
+    let mut written = Vec::new();
+    for row in rows.iter().filter(|row| row.line >= from_line) {
+        let output = output_dir.join(format!("grade-{}.json", row.line));
+        if let Err(error) = write_deploy(options, &row.call(year), &output.to_string_lossy()) {
+            return Err(format!("{}: line {}: {}
+{}, resume with --from-line {}", path, row.line, error, lines_written(&written), row.line));
+        }
+        written.push(row.line);
+        writeln!(
+            out,
+            "line {}: {} {} {} {} -> {}",
+            row.line,
+            row.student_number,
+            row.subject,
+            row.grade_type,
+            import::format_grade(row.grade),
+            output.display()
+        )
+        .map_err(|error| error.to_string())?;
+    }
+    writeln!(out, "{}", lines_written(&written)).map_err(|error| error.to_string())
+}
+
+fn lines_written(lines: &[usize]) -> String {
+    match (lines.first(), lines.last()) {
+        (Some(first), Some(last)) => format!("{} deploys were written, for lines {} to {}", lines.len(), first, last),
+        _ => "no deploys were written".to_string(),
+    }
+}
+
+fn report(path: &str, errors: &[import::RowError]) -> String {
+    let errors = errors.iter().map(|error| format!("{}: {}", path, error)).collect::<Vec<_>>();
+    format!("{}\n{} rows can't be imported, no deploys were written", errors.join("\n"), errors.len())
+}
+
+/// Signs `call` into a deploy at `output`, or prints it if `output` is empty. The arguments go in
+/// through a `session_args_complex` file so any argument type the contract takes can be passed.
+fn write_deploy(options: &Options, call: &NotenCall, output: &str) -> Result<(), String> {
+    // Numbered, so calls from several threads of one process don't share the file.
+    static ARGS_FILES: AtomicU64 = AtomicU64::new(0);
+    let args_file = ARGS_FILES.fetch_add(1, Ordering::Relaxed);
+    let args_path = env::temp_dir().join(format!("noten-cli-{}-{}-args.bin", process::id(), args_file));
+    let args_bytes = call.args.to_bytes().map_err(|error| error.to_string())?;
+    fs::write(&args_path, args_bytes).map_err(|error| error.to_string())?;
+    let args_path = args_path.to_string_lossy().into_owned();
+
+    let result = casper_client::make_deploy(
+        output,
+        DeployStrParams {
+            secret_key: options.flag("secret-key")?,
+            chain_name: options.flag("chain-name")?,
+            ttl: options.optional("ttl").unwrap_or(DEFAULT_TTL),
+            ..Default::default()
+        },
+        SessionStrParams::with_package_hash(options.flag("package")?, "", &call.entry_point, vec![], &args_path),
+        PaymentStrParams::with_amount(options.optional("payment").unwrap_or(DEFAULT_PAYMENT)),
+        options.switch("force"),
+    );
+    let _ = fs::remove_file(&args_path);
+    result.map_err(|error| error.to_string())
+}
+
+fn sign_deploy(options: &Options) -> Result<(), String> {
+    casper_client::sign_deploy_file(
+        options.flag("input")?,
+        options.flag("secret-key")?,
+        options.optional("output").unwrap_or(""),
+        options.switch("force"),
+    )
+    .map_err(|error| error.to_string())
+}
+
+fn decode(options: &Options, out: &mut impl Write) -> Result<(), String> {
+    let (kind, path) = match options.positional.as_slice() {
+        [kind, path] => (kind, path),
+        _ => return Err(USAGE.to_string()),
+    };
+    let scale = options.optional("scale").map(parse_scale).transpose()?.unwrap_or_default();
+    let json = serde_json::from_str(&read(path)?).map_err(|error| format!("{}: {}", path, error))?;
+    let table = decode::table(kind, decode::stored_bytes(&json)?, &scale)?;
+    write!(out, "{}", table).map_err(|error| error.to_string())
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|error| format!("{}: {}", path, error))
+}
+
+/// A grading scale given as `<min>,<max>,<step>`.
+fn parse_scale(scale: &str) -> Result<GradingScale, String> {
+    let steps = scale
+        .split(',')
+        .map(|value| value.trim().parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("'{}' is not a scale", scale))?;
+    match steps.as_slice() {
+        [min, max, step] => Ok(GradingScale { min: *min, max: *max, step: *step }),
+        _ => Err(format!("'{}' is not a scale, expected <min>,<max>,<step>", scale)),
+    }
+}
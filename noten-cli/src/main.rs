@@ -0,0 +1,8 @@
+use std::{env, io, process};
+
+fn main() {
+    if let Err(error) = noten_cli::run(env::args().skip(1), &mut io::stdout()) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,31 @@
+use std::fmt::{self, Display};
+
+/// A two column table of field names and values, printed with the values aligned.
+#[derive(Default)]
+pub struct Table {
+    rows: Vec<(String, String)>,
+}
+
+impl Table {
+    pub fn row<T: Display>(&mut self, field: &str, value: T) -> &mut Self {
+        self.rows.push((field.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn optional<T: Display>(&mut self, field: &str, value: Option<T>) -> &mut Self {
+        match value {
+            Some(value) => self.row(field, value),
+            None => self.row(field, "-"),
+        }
+    }
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.rows.iter().map(|(field, _)| field.len()).max().unwrap_or(0);
+        for (field, value) in &self.rows {
+            writeln!(f, "{:width$}  {}", field, value, width = width)?;
+        }
+        Ok(())
+    }
+}
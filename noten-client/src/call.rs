@@ -0,0 +1,485 @@
+use casper_types::{Key, PublicKey, runtime_args, RuntimeArgs};
+use casper_types::bytesrepr::Bytes;
+use cep47::{Meta, TokenId};
+
+use crate::appointment::Appointment;
+use crate::exam::Exam;
+use crate::origin::Recognition;
+use crate::scale::GradingScale;
+
+/// A call of one of the contract's entry points: what a deploy of the stored contract needs
+/// besides the package hash, payment and signatures.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NotenCall {
+    pub entry_point: String,
+    pub args: RuntimeArgs,
+}
+
+impl NotenCall {
+    fn new(entry_point: &str, args: RuntimeArgs) -> Self {
+        NotenCall { entry_point: entry_point.to_string(), args }
+    }
+
+    pub fn name() -> Self {
+        Self::new("name", runtime_args! {})
+    }
+
+    pub fn symbol() -> Self {
+        Self::new("symbol", runtime_args! {})
+    }
+
+    pub fn meta() -> Self {
+        Self::new("meta", runtime_args! {})
+    }
+
+    pub fn token_meta(token_id: TokenId) -> Self {
+        Self::new("token_meta", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn migrate(token_ids: Vec<TokenId>) -> Self {
+        Self::new("migrate", runtime_args! {
+            "token_ids" => token_ids,
+        })
+    }
+
+    pub fn import_legacy_grade(student: Key, subject: &str, year: u32, grade_type: &str, grade: u32, source_package: Key) -> Self {
+        Self::new("import_legacy_grade", runtime_args! {
+            "student" => student,
+            "subject" => subject.to_string(),
+            "year" => year,
+            "type" => grade_type.to_string(),
+            "grade" => grade,
+            "source_package" => source_package,
+        })
+    }
+
+    pub fn grade(student: Key, subject: &str, year: u32, grade_type: &str, grade: u32) -> Self {
+        Self::new("grade", runtime_args! {
+            "student" => student,
+            "subject" => subject.to_string(),
+            "year" => year,
+            "type" => grade_type.to_string(),
+            "grade" => grade,
+        })
+    }
+
+    pub fn update_grade(student: Key, grade: u32, token_id: TokenId) -> Self {
+        Self::new("update_grade", runtime_args! {
+            "student" => student,
+            "grade" => grade,
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn grade_record(token_id: TokenId) -> Self {
+        Self::new("grade_record", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn register_exam(exam: &Exam) -> Self {
+        Self::new("register_exam", runtime_args! {
+            "subject" => exam.subject.clone(),
+            "class" => exam.class.clone(),
+            "year" => exam.year,
+            "date" => exam.date,
+            "type" => exam.exam_type.clone(),
+        })
+    }
+
+    pub fn grade_exam(student: Key, exam_id: u32, grade: u32) -> Self {
+        Self::new("grade_exam", runtime_args! {
+            "student" => student,
+            "exam_id" => exam_id,
+            "grade" => grade,
+        })
+    }
+
+    pub fn exam(exam_id: u32) -> Self {
+        Self::new("exam", runtime_args! {
+            "exam_id" => exam_id,
+        })
+    }
+
+    pub fn exam_summary(exam_id: u32) -> Self {
+        Self::new("exam_summary", runtime_args! {
+            "exam_id" => exam_id,
+        })
+    }
+
+    pub fn grading_scale() -> Self {
+        Self::new("grading_scale", runtime_args! {})
+    }
+
+    pub fn set_grading_scale(scale: GradingScale) -> Self {
+        Self::new("set_grading_scale", runtime_args! {
+            "min" => scale.min,
+            "max" => scale.max,
+            "step" => scale.step,
+        })
+    }
+
+    pub fn grades_page(offset: u64, limit: u64) -> Self {
+        Self::new("grades_page", runtime_args! {
+            "offset" => offset,
+            "limit" => limit,
+        })
+    }
+
+    pub fn grades_since(event_index: u64, limit: u64) -> Self {
+        Self::new("grades_since", runtime_args! {
+            "event_index" => event_index,
+            "limit" => limit,
+        })
+    }
+
+    pub fn staff_events_since(event_index: u64, limit: u64) -> Self {
+        Self::new("staff_events_since", runtime_args! {
+            "event_index" => event_index,
+            "limit" => limit,
+        })
+    }
+
+    pub fn add_teacher(teacher: Key) -> Self {
+        Self::new("add_teacher", runtime_args! {
+            "teacher" => teacher,
+        })
+    }
+
+    pub fn remove_teacher(teacher: Key) -> Self {
+        Self::new("remove_teacher", runtime_args! {
+            "teacher" => teacher,
+        })
+    }
+
+    pub fn appoint_teacher(teacher: Key, appointment: &Appointment) -> Self {
+        Self::new("appoint_teacher", runtime_args! {
+            "teacher" => teacher,
+            "start" => appointment.start,
+            "end" => appointment.end,
+            "subjects" => appointment.subjects.clone(),
+            "classes" => appointment.classes.clone(),
+        })
+    }
+
+    pub fn appointment(teacher: Key) -> Self {
+        Self::new("appointment", runtime_args! {
+            "teacher" => teacher,
+        })
+    }
+
+    pub fn set_student_class(student: Key, class: &str) -> Self {
+        Self::new("set_student_class", runtime_args! {
+            "student" => student,
+            "class" => class.to_string(),
+        })
+    }
+
+    pub fn student_class(student: Key) -> Self {
+        Self::new("student_class", runtime_args! {
+            "student" => student,
+        })
+    }
+
+    pub fn set_grade_payload(token_id: TokenId, recipients: Vec<PublicKey>, payload: Bytes) -> Self {
+        Self::new("set_grade_payload", runtime_args! {
+            "token_id" => token_id,
+            "recipients" => recipients,
+            "payload" => payload,
+        })
+    }
+
+    pub fn grade_payload(token_id: TokenId) -> Self {
+        Self::new("grade_payload", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn link_pseudonym(pseudonym: Key, commitment: [u8; 32]) -> Self {
+        Self::new("link_pseudonym", runtime_args! {
+            "pseudonym" => pseudonym,
+            "commitment" => commitment,
+        })
+    }
+
+    pub fn claim_pseudonym(pseudonym: Key, nonce: &str) -> Self {
+        Self::new("claim_pseudonym", runtime_args! {
+            "pseudonym" => pseudonym,
+            "nonce" => nonce.to_string(),
+        })
+    }
+
+    pub fn pseudonym_account(pseudonym: Key) -> Self {
+        Self::new("pseudonym_account", runtime_args! {
+            "pseudonym" => pseudonym,
+        })
+    }
+
+    pub fn add_guardian(student: Key, guardian: Key) -> Self {
+        Self::new("add_guardian", runtime_args! {
+            "student" => student,
+            "guardian" => guardian,
+        })
+    }
+
+    pub fn remove_guardian(student: Key, guardian: Key) -> Self {
+        Self::new("remove_guardian", runtime_args! {
+            "student" => student,
+            "guardian" => guardian,
+        })
+    }
+
+    pub fn guardians(student: Key) -> Self {
+        Self::new("guardians", runtime_args! {
+            "student" => student,
+        })
+    }
+
+    pub fn acknowledge_grade(token_id: TokenId) -> Self {
+        Self::new("acknowledge_grade", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn acknowledgement(token_id: TokenId, guardian: Key) -> Self {
+        Self::new("acknowledgement", runtime_args! {
+            "token_id" => token_id,
+            "guardian" => guardian,
+        })
+    }
+
+    pub fn file_appeal(token_id: TokenId, reason: &str) -> Self {
+        Self::new("file_appeal", runtime_args! {
+            "token_id" => token_id,
+            "reason" => reason.to_string(),
+        })
+    }
+
+    pub fn review_appeal(token_id: TokenId) -> Self {
+        Self::new("review_appeal", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn uphold_appeal(token_id: TokenId, grade: u32) -> Self {
+        Self::new("uphold_appeal", runtime_args! {
+            "token_id" => token_id,
+            "grade" => grade,
+        })
+    }
+
+    pub fn reject_appeal(token_id: TokenId) -> Self {
+        Self::new("reject_appeal", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn appeal(token_id: TokenId) -> Self {
+        Self::new("appeal", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn add_reviewer(reviewer: Key) -> Self {
+        Self::new("add_reviewer", runtime_args! {
+            "reviewer" => reviewer,
+        })
+    }
+
+    pub fn remove_reviewer(reviewer: Key) -> Self {
+        Self::new("remove_reviewer", runtime_args! {
+            "reviewer" => reviewer,
+        })
+    }
+
+    pub fn revoke_grade(token_id: TokenId) -> Self {
+        Self::new("revoke_grade", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+
+    pub fn propose_remove_teacher(teacher: Key, deadline: u64) -> Self {
+        Self::new("propose_remove_teacher", runtime_args! {
+            "teacher" => teacher,
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_revoke_grade(token_id: TokenId, deadline: u64) -> Self {
+        Self::new("propose_revoke_grade", runtime_args! {
+            "token_id" => token_id,
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_approval_threshold(threshold: u32, deadline: u64) -> Self {
+        Self::new("propose_approval_threshold", runtime_args! {
+            "threshold" => threshold,
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_add_teacher(teacher: Key, deadline: u64) -> Self {
+        Self::new("propose_add_teacher", runtime_args! {
+            "teacher" => teacher,
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_appoint_teacher(teacher: Key, appointment: &Appointment, deadline: u64) -> Self {
+        Self::new("propose_appoint_teacher", runtime_args! {
+            "teacher" => teacher,
+            "start" => appointment.start,
+            "end" => appointment.end,
+            "subjects" => appointment.subjects.clone(),
+            "classes" => appointment.classes.clone(),
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_unpause(deadline: u64) -> Self {
+        Self::new("propose_unpause", runtime_args! {
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn propose_reopen_term(year: u32, deadline: u64) -> Self {
+        Self::new("propose_reopen_term", runtime_args! {
+            "year" => year,
+            "deadline" => deadline,
+        })
+    }
+
+    pub fn approve_proposal(proposal_id: u32) -> Self {
+        Self::new("approve_proposal", runtime_args! {
+            "proposal_id" => proposal_id,
+        })
+    }
+
+    pub fn proposal(proposal_id: u32) -> Self {
+        Self::new("proposal", runtime_args! {
+            "proposal_id" => proposal_id,
+        })
+    }
+
+    pub fn pause() -> Self {
+        Self::new("pause", runtime_args! {})
+    }
+
+
+    pub fn rollover_year(year: u32, archive: &str, limit: u32) -> Self {
+        Self::new("rollover_year", runtime_args! {
+            "year" => year,
+            "archive" => archive.to_string(),
+            "limit" => limit,
+        })
+    }
+
+    pub fn set_final_class_level(level: u32) -> Self {
+        Self::new("set_final_class_level", runtime_args! {
+            "level" => level,
+        })
+    }
+
+    pub fn reopen_term(year: u32) -> Self {
+        Self::new("reopen_term", runtime_args! {
+            "year" => year,
+        })
+    }
+
+    pub fn average(student: Key, subject: &str, year: u32) -> Self {
+        Self::new("average", runtime_args! {
+            "student" => student,
+            "subject" => subject.to_string(),
+            "year" => year,
+        })
+    }
+
+    pub fn final_average(student: Key, subject: &str, year: u32) -> Self {
+        Self::new("final_average", runtime_args! {
+            "student" => student,
+            "subject" => subject.to_string(),
+            "year" => year,
+        })
+    }
+
+    pub fn graduation(student: Key) -> Self {
+        Self::new("graduation", runtime_args! {
+            "student" => student,
+        })
+    }
+
+    pub fn year_archive(year: u32) -> Self {
+        Self::new("year_archive", runtime_args! {
+            "year" => year,
+        })
+    }
+
+    /// `destination` is the contract package of the receiving school.
+    pub fn issue_transfer(student: Key, token_ids: Vec<TokenId>, destination: Key) -> Self {
+        Self::new("issue_transfer", runtime_args! {
+            "student" => student,
+            "token_ids" => token_ids,
+            "destination" => destination,
+        })
+    }
+
+    pub fn transfer_record(transfer_id: u32) -> Self {
+        Self::new("transfer_record", runtime_args! {
+            "transfer_id" => transfer_id,
+        })
+    }
+
+    /// `school` is the contract package of the sending school.
+    pub fn set_registry(registry: Key) -> Self {
+        Self::new("set_registry", runtime_args! {
+            "registry" => registry,
+        })
+    }
+
+    pub fn import_transfer(school: Key, transfer_id: u32, student: Key) -> Self {
+        Self::new("import_transfer", runtime_args! {
+            "school" => school,
+            "transfer_id" => transfer_id,
+            "student" => student,
+        })
+    }
+
+    pub fn recognise_grade(student: Key, subject: &str, year: u32, grade: u32, recognition: &Recognition) -> Self {
+        Self::new("recognise_grade", runtime_args! {
+            "student" => student,
+            "subject" => subject.to_string(),
+            "year" => year,
+            "grade" => grade,
+            "institution" => recognition.institution.clone(),
+            "original_scale" => recognition.scale.clone(),
+            "original_value" => recognition.value.clone(),
+            "conversion_rule" => recognition.rule.clone(),
+        })
+    }
+
+    pub fn grade_origin(token_id: TokenId) -> Self {
+        Self::new("grade_origin", runtime_args! {
+            "token_id" => token_id,
+        })
+    }
+}
+
+/// Session arguments for installing the contract with `noten.wasm`.
+pub fn install_args(name: &str, symbol: &str, meta: Meta, contract_name: &str) -> RuntimeArgs {
+    runtime_args! {
+        "name" => name.to_string(),
+        "symbol" => symbol.to_string(),
+        "meta" => meta,
+        "contract_name" => contract_name.to_string(),
+    }
+}
+
+/// Session arguments for adding a new version to a package the deploying account installed.
+pub fn upgrade_args(contract_name: &str) -> RuntimeArgs {
+    runtime_args! {
+        "contract_name" => contract_name.to_string(),
+    }
+}
@@ -0,0 +1,207 @@
+//! Grades and diplomas as W3C verifiable credentials.
+//!
+//! The proof of a credential is the contract's state itself: it names the contract package,
+//! dictionary and item the credential was issued from, together with the blake2b digest of the
+//! stored value. A verifier reads that item and checks the credential is exactly the one the
+//! school's state issues today, so updated, revoked or tampered grades fail.
+
+use std::fmt;
+
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use casper_types::bytesrepr::{self, ToBytes};
+use casper_types::{ContractPackageHash, Key, Timestamp, U256};
+use cep47::{Meta, TokenId};
+use serde_json::{json, Value};
+
+use crate::keys;
+use crate::record::GradeRecord;
+use crate::state::{read_dictionary_item, read_named_key, StateReader};
+
+pub const PROOF_TYPE: &str = "CasperContractStateProof";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CredentialError {
+    State(bytesrepr::Error),
+    UnknownGrade,
+    Revoked,
+    NotGraduated,
+    /// The student graduated in a year whose rollover hasn't finished, so the diploma has no date yet.
+    YearNotClosed,
+    /// The credential was issued by another contract package than the one it's checked against,
+    /// or the state isn't read from that package's current contract.
+    WrongIssuer,
+    Malformed(&'static str),
+    /// The credential differs from what the contract's state issues.
+    Mismatch,
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::State(error) => write!(f, "could not read the contract's state: {}", error),
+            CredentialError::UnknownGrade => write!(f, "no such grade"),
+            CredentialError::Revoked => write!(f, "the grade was revoked"),
+            CredentialError::NotGraduated => write!(f, "the student has not graduated"),
+            CredentialError::YearNotClosed => write!(f, "the year of graduation is not closed yet"),
+            CredentialError::WrongIssuer => write!(f, "issued by another school"),
+            CredentialError::Malformed(field) => write!(f, "malformed credential: {}", field),
+            CredentialError::Mismatch => write!(f, "the credential does not match the contract's state"),
+        }
+    }
+}
+
+impl From<bytesrepr::Error> for CredentialError {
+    fn from(error: bytesrepr::Error) -> Self {
+        CredentialError::State(error)
+    }
+}
+
+/// A credential for the grade `token_id` of the school's contract `package`.
+pub fn grade_credential<R: StateReader>(state: &R, package: ContractPackageHash, token_id: TokenId) -> Result<Value, CredentialError> {
+    let item_key = keys::token_key(token_id);
+    let record: GradeRecord = read_dictionary_item(state, keys::GRADE_RECORDS, &item_key)?.ok_or(CredentialError::UnknownGrade)?;
+    if read_dictionary_item::<_, bool>(state, keys::REVOKED_GRADES, &item_key)?.unwrap_or_default() {
+        return Err(CredentialError::Revoked);
+    }
+    let meta: Meta = read_dictionary_item(state, keys::METADATA, &item_key)?.unwrap_or_default();
+    let subject = json!({
+        "id": subject_id(&record.student),
+        "grade": {
+            "tokenId": token_id.to_string(),
+            "subject": record.subject,
+            "year": record.year,
+            "type": record.grade_type,
+            "grade": record.grade,
+            "teacher": record.teacher.to_formatted_string(),
+            "origin": meta.get("kind").map(String::as_str).unwrap_or("school"),
+        }
+    });
+    let issued = Timestamp::from(record.issued_at).to_string();
+    credential(
+        state,
+        package,
+        &format!("grade:{}", token_id),
+        "GradeCredential",
+        &issued,
+        subject,
+        (keys::GRADE_RECORDS, &item_key, digest(&record)?),
+    )
+}
+
+/// A diploma for `student`, with the final averages of the year they graduated in.
+pub fn diploma_credential<R: StateReader>(state: &R, package: ContractPackageHash, student: Key) -> Result<Value, CredentialError> {
+    let item_key = keys::key_to_str(&student);
+    let year: u32 = read_dictionary_item(state, keys::GRADUATES, &item_key)?.ok_or(CredentialError::NotGraduated)?;
+    let (_, closed_at, _): (String, u64, u64) =
+        read_dictionary_item(state, keys::YEAR_ARCHIVES, &year.to_string())?.ok_or(CredentialError::YearNotClosed)?;
+    let subjects: Vec<String> = read_dictionary_item(state, keys::STUDENT_SUBJECTS, &keys::student_year_key(&student, year))?.unwrap_or_default();
+    let mut final_averages = Vec::new();
+    for subject in subjects {
+        let average: Option<u32> = read_dictionary_item(state, keys::FINAL_AVERAGES, &keys::subject_year_key(&student, &subject, year))?;
+        if let Some(average) = average {
+            final_averages.push(json!({ "subject": subject, "average": average }));
+        }
+    }
+    let subject = json!({
+        "id": subject_id(&student),
+        "diploma": {
+            "year": year,
+            "finalAverages": final_averages,
+        }
+    });
+    let issued = Timestamp::from(closed_at).to_string();
+    credential(
+        state,
+        package,
+        &format!("diploma:{}", item_key),
+        "DiplomaCredential",
+        &issued,
+        subject,
+        (keys::GRADUATES, &item_key, digest(&year)?),
+    )
+}
+
+/// Checks `credential` against the state of the school's contract `package`. `state` has to be
+/// read from the package's current contract, otherwise any contract's state could vouch for it.
+pub fn verify<R: StateReader>(state: &R, package: ContractPackageHash, credential: &Value) -> Result<(), CredentialError> {
+    let proof = credential.get("proof").ok_or(CredentialError::Malformed("proof"))?;
+    if proof["type"] != PROOF_TYPE {
+        return Err(CredentialError::Malformed("proof type"));
+    }
+    if proof["contractPackage"] != Key::Hash(package.value()).to_formatted_string() {
+        return Err(CredentialError::WrongIssuer);
+    }
+    if state.contract_package() != Some(package) {
+        return Err(CredentialError::WrongIssuer);
+    }
+    let item_key = proof["dictionaryItemKey"].as_str().ok_or(CredentialError::Malformed("dictionaryItemKey"))?;
+    let expected = match proof["dictionary"].as_str() {
+        Some(keys::GRADE_RECORDS) => {
+            let token_id = U256::from_dec_str(item_key).map_err(|_| CredentialError::Malformed("dictionaryItemKey"))?;
+            grade_credential(state, package, token_id)?
+        }
+        Some(keys::GRADUATES) => {
+            let student = credential["credentialSubject"]["id"]
+                .as_str()
+                .and_then(|id| id.strip_prefix("urn:casper:"))
+                .and_then(|key| Key::from_formatted_str(key).ok())
+                .ok_or(CredentialError::Malformed("credentialSubject"))?;
+            diploma_credential(state, package, student)?
+        }
+        _ => return Err(CredentialError::Malformed("dictionary")),
+    };
+    if expected == *credential {
+        Ok(())
+    } else {
+        Err(CredentialError::Mismatch)
+    }
+}
+
+fn credential<R: StateReader>(
+    state: &R,
+    package: ContractPackageHash,
+    id: &str,
+    credential_type: &str,
+    issued: &str,
+    subject: Value,
+    (dictionary, item_key, digest): (&str, &str, String),
+) -> Result<Value, CredentialError> {
+    let package = Key::Hash(package.value()).to_formatted_string();
+    let meta: Meta = read_named_key(state, "meta")?.unwrap_or_default();
+    let name: String = read_named_key(state, "name")?.unwrap_or_default();
+    Ok(json!({
+        "@context": ["https://www.w3.org/2018/credentials/v1"],
+        "id": format!("urn:casper:{}:{}", package, id),
+        "type": ["VerifiableCredential", credential_type],
+        "issuer": {
+            "id": format!("urn:casper:{}", package),
+            "name": meta.get("school").cloned().unwrap_or(name),
+        },
+        "issuanceDate": issued,
+        "credentialSubject": subject,
+        "proof": {
+            "type": PROOF_TYPE,
+            "created": issued,
+            "proofPurpose": "assertionMethod",
+            "verificationMethod": format!("urn:casper:{}", package),
+            "contractPackage": package,
+            "dictionary": dictionary,
+            "dictionaryItemKey": item_key,
+            "digest": digest,
+        }
+    }))
+}
+
+fn subject_id(student: &Key) -> String {
+    format!("urn:casper:{}", student.to_formatted_string())
+}
+
+/// blake2b of the value as the contract stores it.
+fn digest<T: ToBytes>(value: &T) -> Result<String, CredentialError> {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(value.to_bytes()?);
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    Ok(hex::encode(ret))
+}
@@ -0,0 +1,11 @@
+use casper_types::CLValue;
+use casper_types::bytesrepr::{self, FromBytes};
+
+/// Decodes a dictionary item the way the contract stores it, as an `Option` of the value.
+///
+/// The contract's own types are stored as `CLType::Any`, so this skips the type check
+/// `CLValue::into_t` would do.
+pub fn dictionary_value<T: FromBytes>(value: &CLValue) -> Result<Option<T>, bytesrepr::Error> {
+    bytesrepr::deserialize(value.inner_bytes().clone())
+}
+
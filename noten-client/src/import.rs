@@ -0,0 +1,209 @@
+//! Grades exported from the school administration software as CSV, with the columns student
+//! number, subject, type, grade and comment after a header row naming them.
+//!
+//! Grades are written the way teachers write them, `4.5` or `4,5`, and become `45` on-chain.
+//! Every row is checked before a single call is built, so a file either imports completely or
+//! comes back with the errors of all rows that need fixing.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use casper_types::Key;
+
+use crate::call::NotenCall;
+use crate::pseudonym;
+use crate::scale::GradingScale;
+
+/// Header of a grades CSV; the comment column may be left out.
+const GRADE_COLUMNS: [&str; 5] = ["student number", "subject", "type", "grade", "comment"];
+const ROSTER_COLUMNS: [&str; 2] = ["student number", "key"];
+
+/// How student numbers map to the keys grades are issued to.
+pub enum Students {
+    /// A roster of student numbers and their keys, see [`read_roster`].
+    Roster(BTreeMap<String, Key>),
+    /// The school's pseudonyms, derived from the student number and the school salt.
+    Pseudonyms { salt: String },
+}
+
+impl Students {
+    pub fn key(&self, student_number: &str) -> Option<Key> {
+        match self {
+            Students::Roster(roster) => roster.get(student_number).copied(),
+            Students::Pseudonyms { salt } => Some(pseudonym::pseudonym(student_number, salt)),
+        }
+    }
+}
+
+/// A grade read from the CSV, ready to be issued.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GradeRow {
+    /// Line in the file, counting the header as line 1.
+    pub line: usize,
+    pub student_number: String,
+    pub student: Key,
+    pub subject: String,
+    pub grade_type: String,
+    pub grade: u32,
+    /// Stays off-chain; the contract keeps no free text with a grade.
+    pub comment: String,
+}
+
+impl GradeRow {
+    pub fn call(&self, year: u32) -> NotenCall {
+        NotenCall::grade(self.student, &self.subject, year, &self.grade_type, self.grade)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Reads the grades of a CSV export, or the errors of every row that can't be imported.
+pub fn read_grades(csv: &str, students: &Students, scale: &GradingScale) -> Result<Vec<GradeRow>, Vec<RowError>> {
+    let mut rows = Vec::new();
+    let mut records = records(csv);
+    let mut errors: Vec<RowError> = header(records.next(), &GRADE_COLUMNS, 4).err().into_iter().collect();
+    for (line, fields) in records {
+        match grade_row(line, &fields, students, scale) {
+            Ok(row) => rows.push(row),
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(rows)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Reads a roster of student numbers and formatted keys (`account-hash-…`, `hash-…`) after the
+/// header row `student number,key`.
+pub fn read_roster(csv: &str) -> Result<BTreeMap<String, Key>, Vec<RowError>> {
+    let mut roster = BTreeMap::new();
+    let mut records = records(csv);
+    let mut errors: Vec<RowError> = header(records.next(), &ROSTER_COLUMNS, 2).err().into_iter().collect();
+    for (line, fields) in records {
+        let row = match fields.as_slice() {
+            [number, key] => Key::from_formatted_str(key)
+                .map(|key| (number.clone(), key))
+                .map_err(|_| format!("'{}' is not a formatted key", key)),
+            _ => Err(format!("expected 2 columns, got {}", fields.len())),
+        };
+        match row {
+            Ok((number, _)) if roster.contains_key(&number) => {
+                errors.push(RowError { line, message: format!("student number {} is listed twice", number) })
+            }
+            Ok((number, key)) => {
+                roster.insert(number, key);
+            }
+            Err(message) => errors.push(RowError { line, message }),
+        }
+    }
+    if errors.is_empty() {
+        Ok(roster)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Checks that the first record names the `columns`, in any case, of which those after the first
+/// `required` may be left out. Without it the first row would be taken for the header and skipped.
+fn header(record: Option<(usize, Vec<String>)>, columns: &[&str], required: usize) -> Result<(), RowError> {
+    let expected = columns.join(", ");
+    let (line, fields) = record.ok_or_else(|| RowError { line: 1, message: format!("expected the header {}, the file is empty", expected) })?;
+    let named = fields.len() >= required && fields.len() <= columns.len() && fields.iter().zip(columns).all(|(field, column)| field.eq_ignore_ascii_case(column));
+    if named {
+        Ok(())
+    } else {
+        Err(RowError { line, message: format!("expected the header {}, got {}", expected, fields.join(", ")) })
+    }
+}
+
+fn grade_row(line: usize, fields: &[String], students: &Students, scale: &GradingScale) -> Result<GradeRow, String> {
+    let (student_number, subject, grade_type, grade, comment) = match fields {
+        [number, subject, grade_type, grade] => (number, subject, grade_type, grade, String::new()),
+        [number, subject, grade_type, grade, comment] => (number, subject, grade_type, grade, comment.clone()),
+        _ => return Err(format!("expected 5 columns, got {}", fields.len())),
+    };
+    let student = students
+        .key(student_number)
+        .ok_or_else(|| format!("student number {} is not on the roster", student_number))?;
+    if subject.is_empty() {
+        return Err("subject is empty".to_string());
+    }
+    if grade_type.is_empty() {
+        return Err("type is empty".to_string());
+    }
+    let grade = parse_grade(grade).ok_or_else(|| format!("'{}' is not a grade", grade))?;
+    if !scale.contains(grade) {
+        return Err(format!("{} is not on the grading scale", format_grade(grade)));
+    }
+    Ok(GradeRow {
+        line,
+        student_number: student_number.clone(),
+        student,
+        subject: subject.clone(),
+        grade_type: grade_type.clone(),
+        grade,
+        comment,
+    })
+}
+
+/// `4.5`, `4,5` and `4` as the contract's tenths, `45`, `45` and `40`.
+pub fn parse_grade(grade: &str) -> Option<u32> {
+    let grade = grade.replace(',', ".");
+    let (whole, tenths) = match grade.split_once('.') {
+        Some((whole, tenths)) if tenths.len() == 1 => (whole, tenths),
+        Some(_) => return None,
+        None => (grade.as_str(), "0"),
+    };
+    let whole: u32 = whole.parse().ok()?;
+    let tenths: u32 = tenths.parse().ok()?;
+    whole.checked_mul(10)?.checked_add(tenths)
+}
+
+pub fn format_grade(grade: u32) -> String {
+    format!("{}.{}", grade / 10, grade % 10)
+}
+
+/// Non-empty lines split into fields, with their line numbers. Fields may be quoted with `"`,
+/// and are separated by `;` if the header has one, as spreadsheets with decimal commas export them.
+fn records(csv: &str) -> impl Iterator<Item = (usize, Vec<String>)> + '_ {
+    let separator = match csv.lines().next() {
+        Some(header) if header.contains(';') => ';',
+        _ => ',',
+    };
+    csv.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(move |(index, line)| (index + 1, fields(line, separator)))
+}
+
+fn fields(line: &str, separator: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
@@ -0,0 +1,80 @@
+//! Names of the contract's dictionaries and the item keys it stores values under, for reading
+//! them straight from global state.
+
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use casper_types::{Key, U256};
+use casper_types::bytesrepr::ToBytes;
+use cep47::TokenId;
+
+pub const METADATA: &str = "metadata";
+pub const OWNED_TOKENS_BY_INDEX: &str = "owned_tokens_by_index";
+pub const BALANCES: &str = "balances";
+pub const ADMINS: &str = "admins";
+pub const GRADE_RECORDS: &str = "grade_records";
+pub const GRADE_ORIGINS: &str = "grade_origins";
+pub const GRADE_PAYLOADS: &str = "grade_payloads";
+pub const PSEUDONYM_ACCOUNTS: &str = "pseudonym_accounts";
+pub const GUARDIANS: &str = "guardians";
+pub const ACKNOWLEDGEMENTS: &str = "grade_acknowledgements";
+pub const APPEALS: &str = "appeals";
+pub const REVOKED_GRADES: &str = "revoked_grades";
+pub const PROPOSALS: &str = "proposals";
+pub const APPOINTMENTS: &str = "appointments";
+pub const STUDENT_CLASSES: &str = "student_classes";
+pub const STUDENTS: &str = "students";
+pub const STUDENT_SUBJECTS: &str = "student_subjects";
+pub const GRADE_TOTALS: &str = "grade_totals";
+pub const FINAL_AVERAGES: &str = "final_averages";
+pub const GRADUATES: &str = "graduates";
+pub const YEAR_ARCHIVES: &str = "year_archives";
+pub const TRANSFERS: &str = "transfers";
+pub const GRADES: &str = "grades";
+pub const EVENTS: &str = "events";
+pub const STAFF_EVENTS: &str = "staff_events";
+pub const EXAMS: &str = "exams";
+pub const EXAM_STATS: &str = "exam_stats";
+
+/// Item key for values stored per account or contract, the same as cep47's `key_to_str`.
+pub fn key_to_str(key: &Key) -> String {
+    match key {
+        Key::Account(account) => account.to_string(),
+        Key::Hash(package) => hex::encode(package),
+        _ => panic!("Unexpected key type"),
+    }
+}
+
+/// Item key for values stored per account and some value, the same as cep47's `key_and_value_to_str`.
+pub fn key_and_value_to_str<T: ToBytes>(key: &Key, value: &T) -> String {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(key.to_bytes().unwrap().as_slice());
+    hasher.update(value.to_bytes().unwrap().as_slice());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    hex::encode(ret)
+}
+
+/// Item key for anything stored per grade, e.g. in `METADATA` or `GRADE_RECORDS`.
+pub fn token_key(token_id: TokenId) -> String {
+    token_id.to_string()
+}
+
+/// Item key for the `index`th grade of `owner` in `OWNED_TOKENS_BY_INDEX`.
+pub fn owned_token_key(owner: &Key, index: U256) -> String {
+    key_and_value_to_str(owner, &index)
+}
+
+/// Item key in `GRADE_TOTALS` and `FINAL_AVERAGES`.
+pub fn subject_year_key(student: &Key, subject: &str, year: u32) -> String {
+    key_and_value_to_str(student, &(subject.to_string(), year))
+}
+
+/// Item key in `STUDENT_SUBJECTS`.
+pub fn student_year_key(student: &Key, year: u32) -> String {
+    key_and_value_to_str(student, &year)
+}
+
+/// Item key in `ACKNOWLEDGEMENTS`.
+pub fn acknowledgement_key(guardian: &Key, token_id: TokenId) -> String {
+    key_and_value_to_str(guardian, &token_id)
+}
@@ -0,0 +1,23 @@
+//! Client side of the noten contract: typed entry point calls, dictionary keys and decoders for
+//! what the contract stores, plus the off-chain parts of encrypted payloads and pseudonyms.
+//!
+//! The contract's own types come from `noten-types`, which the contract is built with, so they
+//! always serialize the same way the deployed contract does.
+
+pub use noten_types::{appeal, appointment, error, exam, origin, page, proposal, record, scale, total, transfer};
+
+pub mod call;
+pub mod credential;
+pub mod decode;
+pub mod import;
+pub mod keys;
+pub mod payload;
+mod pdf;
+pub mod pseudonym;
+pub mod rpc;
+pub mod state;
+pub mod transcript;
+
+pub use call::NotenCall;
+pub use error::NotenError;
+pub use state::StateReader;
@@ -0,0 +1,131 @@
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{ChaCha20Poly1305, Key as CipherKey, Nonce};
+use curve25519_dalek::constants::X25519_BASEPOINT;
+use curve25519_dalek::edwards::CompressedEdwardsY;
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use curve25519_dalek::scalar::Scalar;
+use rand::Rng;
+use sha2::{Digest, Sha512};
+
+use casper_types::{PublicKey, SecretKey};
+use casper_types::bytesrepr::Bytes;
+
+const POINT_LENGTH: usize = 32;
+const NONCE_LENGTH: usize = 12;
+const WRAPPED_KEY_LENGTH: usize = 48;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum PayloadError {
+    /// Only ed25519 keys can be converted for key agreement.
+    UnsupportedKey,
+    /// The public key is not a valid curve point.
+    InvalidKey,
+    /// The secret key does not belong to any of the recipients.
+    NotARecipient,
+    /// The envelope is truncated or was not encrypted for these recipients.
+    Malformed,
+}
+
+/// Encrypts `plaintext` so that every key in `recipients` can decrypt it.
+///
+/// The envelope is `ephemeral key || nonce || one wrapped content key per recipient || ciphertext`,
+/// with the wrapped keys in the same order as `recipients`, which is how the contract stores them.
+pub fn encrypt(recipients: &[PublicKey], plaintext: &[u8]) -> Result<Bytes, PayloadError> {
+    let mut rng = rand::thread_rng();
+    let ephemeral = clamped(rng.gen());
+    let ephemeral_public = X25519_BASEPOINT * ephemeral;
+    let content_key: [u8; 32] = rng.gen();
+    let nonce: [u8; NONCE_LENGTH] = rng.gen();
+
+    let mut envelope = Vec::new();
+    envelope.extend_from_slice(ephemeral_public.as_bytes());
+    envelope.extend_from_slice(&nonce);
+    for recipient in recipients {
+        let recipient = montgomery(recipient)?;
+        let wrapping_key = wrapping_key(&(recipient * ephemeral), &ephemeral_public, &recipient);
+        let wrapped = ChaCha20Poly1305::new(&CipherKey::from(wrapping_key))
+            .encrypt(&Nonce::from([0u8; NONCE_LENGTH]), &content_key[..])
+            .map_err(|_| PayloadError::Malformed)?;
+        envelope.extend_from_slice(&wrapped);
+    }
+    let ciphertext = ChaCha20Poly1305::new(&CipherKey::from(content_key))
+        .encrypt(&Nonce::from(nonce), plaintext)
+        .map_err(|_| PayloadError::Malformed)?;
+    envelope.extend_from_slice(&ciphertext);
+    Ok(Bytes::from(envelope))
+}
+
+/// Decrypts a payload stored by `set_grade_payload` with the secret key of one of its recipients.
+pub fn decrypt(secret_key: &SecretKey, recipients: &[PublicKey], payload: &[u8]) -> Result<Vec<u8>, PayloadError> {
+    let public_key = PublicKey::from(secret_key);
+    let position = recipients
+        .iter()
+        .position(|recipient| *recipient == public_key)
+        .ok_or(PayloadError::NotARecipient)?;
+    let header = POINT_LENGTH + NONCE_LENGTH;
+    let body = header + recipients.len() * WRAPPED_KEY_LENGTH;
+    if payload.len() < body {
+        return Err(PayloadError::Malformed);
+    }
+
+    let mut ephemeral_public = [0u8; POINT_LENGTH];
+    ephemeral_public.copy_from_slice(&payload[..POINT_LENGTH]);
+    let ephemeral_public = MontgomeryPoint(ephemeral_public);
+    let mut nonce = [0u8; NONCE_LENGTH];
+    nonce.copy_from_slice(&payload[POINT_LENGTH..header]);
+    let wrapped = &payload[header + position * WRAPPED_KEY_LENGTH..header + (position + 1) * WRAPPED_KEY_LENGTH];
+
+    let secret = secret_scalar(secret_key)?;
+    let wrapping_key = wrapping_key(&(ephemeral_public * secret), &ephemeral_public, &montgomery(&public_key)?);
+    let unwrapped = ChaCha20Poly1305::new(&CipherKey::from(wrapping_key))
+        .decrypt(&Nonce::from([0u8; NONCE_LENGTH]), wrapped)
+        .map_err(|_| PayloadError::Malformed)?;
+    let mut content_key = [0u8; 32];
+    content_key.copy_from_slice(&unwrapped);
+    ChaCha20Poly1305::new(&CipherKey::from(content_key))
+        .decrypt(&Nonce::from(nonce), &payload[body..])
+        .map_err(|_| PayloadError::Malformed)
+}
+
+fn clamped(mut bytes: [u8; 32]) -> Scalar {
+    bytes[0] &= 248;
+    bytes[31] &= 127;
+    bytes[31] |= 64;
+    Scalar::from_bits(bytes)
+}
+
+/// The x25519 counterpart of an ed25519 public key.
+fn montgomery(public_key: &PublicKey) -> Result<MontgomeryPoint, PayloadError> {
+    match public_key {
+        PublicKey::Ed25519(key) => CompressedEdwardsY(*key.as_bytes())
+            .decompress()
+            .map(|point| point.to_montgomery())
+            .ok_or(PayloadError::InvalidKey),
+        _ => Err(PayloadError::UnsupportedKey),
+    }
+}
+
+/// The x25519 counterpart of an ed25519 secret key, derived the same way ed25519 expands its seed.
+fn secret_scalar(secret_key: &SecretKey) -> Result<Scalar, PayloadError> {
+    match secret_key {
+        SecretKey::Ed25519(key) => {
+            let expanded = Sha512::digest(key.as_bytes());
+            let mut bytes = [0u8; 32];
+            bytes.copy_from_slice(&expanded[..32]);
+            Ok(clamped(bytes))
+        }
+        _ => Err(PayloadError::UnsupportedKey),
+    }
+}
+
+fn wrapping_key(shared: &MontgomeryPoint, ephemeral: &MontgomeryPoint, recipient: &MontgomeryPoint) -> [u8; 32] {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    hasher.update(shared.as_bytes());
+    hasher.update(ephemeral.as_bytes());
+    hasher.update(recipient.as_bytes());
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    ret
+}
@@ -0,0 +1,91 @@
+//! Just enough PDF to print text: pages of monospaced lines in the standard Courier fonts, which
+//! every reader has, so nothing needs to be embedded.
+
+const PAGE_WIDTH: u32 = 595;
+const PAGE_HEIGHT: u32 = 842;
+const MARGIN: u32 = 56;
+const LEADING: u32 = 14;
+const FONT_SIZE: u32 = 10;
+const LINES_PER_PAGE: usize = ((PAGE_HEIGHT - 2 * MARGIN) / LEADING) as usize;
+
+pub enum Line {
+    Heading(String),
+    Text(String),
+}
+
+/// A PDF of `lines`, continued on a new page whenever one is full.
+pub fn document(lines: &[Line]) -> Vec<u8> {
+    let pages: Vec<&[Line]> = if lines.is_empty() { vec![lines] } else { lines.chunks(LINES_PER_PAGE).collect() };
+    // Objects 1 and 2 are the catalog and page tree, 3 and 4 the fonts, then a page and its
+    // content stream for each page.
+    let page_id = |page: usize| 5 + 2 * page;
+    let kids = (0..pages.len()).map(|page| format!("{} 0 R", page_id(page))).collect::<Vec<_>>().join(" ");
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids, pages.len()).into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (page, lines) in pages.iter().enumerate() {
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                PAGE_WIDTH,
+                PAGE_HEIGHT,
+                page_id(page) + 1
+            )
+            .into_bytes(),
+        );
+        let content = content(lines);
+        let mut stream = format!("<< /Length {} >>\nstream\n", content.len()).into_bytes();
+        stream.extend(content);
+        stream.extend(b"\nendstream");
+        objects.push(stream);
+    }
+
+    let mut pdf = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.extend(format!("{} 0 obj\n", index + 1).into_bytes());
+        pdf.extend(object);
+        pdf.extend(b"\nendobj\n");
+    }
+    let xref = pdf.len();
+    pdf.extend(format!("xref\n0 {}\n0000000000 65535 f \n", objects.len() + 1).into_bytes());
+    for offset in offsets {
+        pdf.extend(format!("{:010} 00000 n \n", offset).into_bytes());
+    }
+    pdf.extend(format!("trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n", objects.len() + 1, xref).into_bytes());
+    pdf
+}
+
+fn content(lines: &[Line]) -> Vec<u8> {
+    let mut content = format!("BT\n{} TL\n{} {} Td\n", LEADING, MARGIN, PAGE_HEIGHT - MARGIN).into_bytes();
+    for line in lines {
+        let (font, text) = match line {
+            Line::Heading(text) => ("F2", text),
+            Line::Text(text) => ("F1", text),
+        };
+        content.extend(format!("/{} {} Tf\n(", font, FONT_SIZE).into_bytes());
+        content.extend(escape(text));
+        content.extend(b") Tj T*\n");
+    }
+    content.extend(b"ET");
+    content
+}
+
+/// `text` in WinAnsiEncoding, as far as Latin-1 goes, with the string delimiters escaped.
+fn escape(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for c in text.chars() {
+        match c {
+            '(' | ')' | '\\' => bytes.extend([b'\\', c as u8]),
+            c if (c as u32) < 0x20 => bytes.push(b' '),
+            c if (c as u32) <= 0xff => bytes.push(c as u32 as u8),
+            _ => bytes.push(b'?'),
+        }
+    }
+    bytes
+}
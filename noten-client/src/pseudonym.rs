@@ -0,0 +1,31 @@
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+
+use casper_types::Key;
+use casper_types::account::AccountHash;
+use casper_types::bytesrepr::ToBytes;
+
+fn blake2b(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = VarBlake2b::new(32).unwrap();
+    for part in parts {
+        hasher.update(part);
+    }
+    let mut ret = [0u8; 32];
+    hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
+    ret
+}
+
+/// The key a school issues grades to instead of the student's account: `blake2b(student number || school salt)`.
+pub fn pseudonym(student_number: &str, salt: &str) -> Key {
+    Key::Hash(blake2b(&[student_number.as_bytes(), salt.as_bytes()]))
+}
+
+/// The commitment `link_pseudonym` expects; the student claims it by calling `claim_pseudonym` with `nonce`.
+/// The commitment keeps the link private, the claim makes it public.
+pub fn commitment(pseudonym: &Key, student: &AccountHash, nonce: &str) -> [u8; 32] {
+    blake2b(&[
+        &pseudonym.to_bytes().unwrap(),
+        &Key::Account(*student).to_bytes().unwrap(),
+        nonce.as_bytes(),
+    ])
+}
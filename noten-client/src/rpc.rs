@@ -0,0 +1,139 @@
+//! Reads a contract's state from a node's JSON-RPC API.
+
+use std::cell::Cell;
+use std::fmt;
+
+use casper_types::{CLType, CLValue, ContractPackageHash, Key, StoredValue};
+use serde_json::{json, Value};
+
+use crate::state::StateReader;
+
+#[derive(Debug)]
+pub enum RpcError {
+    Http(String),
+    Rpc { code: i64, message: String },
+    /// The node answered with something that isn't a JSON-RPC response.
+    Malformed,
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RpcError::Http(error) => write!(f, "{}", error),
+            RpcError::Rpc { code, message } => write!(f, "RPC error {}: {}", code, message),
+            RpcError::Malformed => write!(f, "malformed RPC response"),
+        }
+    }
+}
+
+/// The state of the contract `contract` (its `hash-…` contract hash) at one state root hash, so
+/// everything read through it is consistent.
+pub struct RpcState {
+    node: String,
+    contract: String,
+    state_root_hash: String,
+    next_id: Cell<u64>,
+}
+
+impl RpcState {
+    /// Reads from the latest state root hash of the node at `node`, e.g. `http://localhost:7777/rpc`.
+    pub fn connect(node: &str, contract: Key) -> Result<Self, RpcError> {
+        let mut state = RpcState {
+            node: node.to_string(),
+            contract: contract.to_formatted_string(),
+            state_root_hash: String::new(),
+            next_id: Cell::new(0),
+        };
+        let result = state.request("chain_get_state_root_hash", json!({}))?;
+        state.state_root_hash = result["state_root_hash"].as_str().ok_or(RpcError::Malformed)?.to_string();
+        Ok(state)
+    }
+
+    pub fn state_root_hash(&self) -> &str {
+        &self.state_root_hash
+    }
+
+    pub fn request(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        let response = ureq::post(&self.node)
+            .set("Content-Type", "application/json")
+            .send_string(&body.to_string())
+            .map_err(|error| RpcError::Http(error.to_string()))?
+            .into_string()
+            .map_err(|error| RpcError::Http(error.to_string()))?;
+        let mut response: Value = serde_json::from_str(&response).map_err(|_| RpcError::Malformed)?;
+        if let Some(error) = response.get("error") {
+            return Err(RpcError::Rpc {
+                code: error["code"].as_i64().unwrap_or_default(),
+                message: error["message"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+        response.get_mut("result").map(Value::take).ok_or(RpcError::Malformed)
+    }
+
+    fn stored_value(&self, method: &str, params: Value) -> Option<CLValue> {
+        let result = self.request(method, params).ok()?;
+        let bytes = hex::decode(result.pointer("/stored_value/CLValue/bytes")?.as_str()?).ok()?;
+        // The values are decoded from their bytes alone, as the contract's own types are `CLType::Any`.
+        Some(CLValue::from_components(CLType::Any, bytes))
+    }
+
+    /// A contract, package or account itself rather than a value under one of its named keys.
+    fn stored(&self, key: &str) -> Option<StoredValue> {
+        let mut result = self
+            .request(
+                "query_global_state",
+                json!({
+                    "state_identifier": { "StateRootHash": self.state_root_hash },
+                    "key": key,
+                    "path": [],
+                }),
+            )
+            .ok()?;
+        serde_json::from_value(result.get_mut("stored_value")?.take()).ok()
+    }
+}
+
+/// Values that can't be read, because they don't exist or the node can't be reached, are `None`.
+impl StateReader for RpcState {
+    fn named_key(&self, name: &str) -> Option<CLValue> {
+        self.stored_value(
+            "query_global_state",
+            json!({
+                "state_identifier": { "StateRootHash": self.state_root_hash },
+                "key": self.contract,
+                "path": [name],
+            }),
+        )
+    }
+
+    fn dictionary_item(&self, dictionary: &str, key: &str) -> Option<CLValue> {
+        self.stored_value(
+            "state_get_dictionary_item",
+            json!({
+                "state_root_hash": self.state_root_hash,
+                "dictionary_identifier": {
+                    "ContractNamedKey": {
+                        "key": self.contract,
+                        "dictionary_name": dictionary,
+                        "dictionary_item_key": key,
+                    }
+                },
+            }),
+        )
+    }
+
+    fn contract_package(&self) -> Option<ContractPackageHash> {
+        let package = match self.stored(&self.contract)? {
+            StoredValue::Contract(contract) => contract.contract_package_hash(),
+            _ => return None,
+        };
+        let current = match self.stored(&Key::Hash(package.value()).to_formatted_string())? {
+            StoredValue::ContractPackage(package) => package.current_contract_hash()?,
+            _ => return None,
+        };
+        (Key::Hash(current.value()).to_formatted_string() == self.contract).then(|| package)
+    }
+}
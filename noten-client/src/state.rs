@@ -0,0 +1,29 @@
+//! Read access to a deployed contract's state, whether it lives on a node or in a test builder.
+
+use casper_types::bytesrepr::{self, FromBytes};
+use casper_types::{CLValue, ContractPackageHash};
+
+use crate::decode::dictionary_value;
+
+pub trait StateReader {
+    /// The value stored under one of the contract's named keys.
+    fn named_key(&self, name: &str) -> Option<CLValue>;
+    /// An item of one of the contract's dictionaries, see [`crate::keys`].
+    fn dictionary_item(&self, dictionary: &str, key: &str) -> Option<CLValue>;
+    /// The package the contract belongs to, if the contract is that package's current version.
+    fn contract_package(&self) -> Option<ContractPackageHash>;
+}
+
+pub fn read_named_key<R: StateReader, T: FromBytes>(state: &R, name: &str) -> Result<Option<T>, bytesrepr::Error> {
+    state
+        .named_key(name)
+        .map(|value| bytesrepr::deserialize(value.inner_bytes().clone()))
+        .transpose()
+}
+
+pub fn read_dictionary_item<R: StateReader, T: FromBytes>(state: &R, dictionary: &str, key: &str) -> Result<Option<T>, bytesrepr::Error> {
+    match state.dictionary_item(dictionary, key) {
+        Some(value) => dictionary_value(&value),
+        None => Ok(None),
+    }
+}
@@ -0,0 +1,206 @@
+//! A student's transcript as read from global state, rendered as JSON, CSV or a printable
+//! report card.
+
+use std::collections::BTreeSet;
+
+use casper_types::bytesrepr;
+use casper_types::{Key, U256};
+use cep47::{Meta, TokenId};
+use serde_json::json;
+
+use crate::import::format_grade;
+use crate::keys;
+use crate::pdf::{self, Line};
+use crate::state::{read_dictionary_item, read_named_key, StateReader};
+use crate::total;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptGrade {
+    pub token_id: TokenId,
+    pub subject: String,
+    pub year: u32,
+    pub grade_type: String,
+    pub grade: u32,
+    /// `school` for grades issued here, otherwise the `kind` of an imported grade.
+    pub origin: String,
+    pub revoked: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TranscriptAverage {
+    pub subject: String,
+    pub year: u32,
+    pub average: Option<u32>,
+    pub final_average: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transcript {
+    /// The `school` in the contract's `meta`, or else the contract's name.
+    pub school: String,
+    pub meta: Meta,
+    pub student: Key,
+    pub grades: Vec<TranscriptGrade>,
+    /// Per subject and year, from the running totals the contract keeps, which transferred and
+    /// revoked grades don't count towards, rounded with `total::average` like the contract does.
+    pub averages: Vec<TranscriptAverage>,
+    pub graduated: Option<u32>,
+}
+
+impl Transcript {
+    pub fn read<R: StateReader>(state: &R, student: Key) -> Result<Transcript, bytesrepr::Error> {
+        let name: String = read_named_key(state, "name")?.unwrap_or_default();
+        let meta: Meta = read_named_key(state, "meta")?.unwrap_or_default();
+        let school = meta.get("school").cloned().unwrap_or(name);
+
+        let mut grades = Vec::new();
+        let mut index = U256::zero();
+        while let Some(token_id) =
+            read_dictionary_item::<_, TokenId>(state, keys::OWNED_TOKENS_BY_INDEX, &keys::owned_token_key(&student, index))?
+        {
+            let meta: Meta = read_dictionary_item(state, keys::METADATA, &keys::token_key(token_id))?.unwrap_or_default();
+            let field = |name: &str| meta.get(name).cloned().unwrap_or_default();
+            grades.push(TranscriptGrade {
+                token_id,
+                subject: field("subject"),
+                year: field("year").parse().unwrap_or_default(),
+                grade_type: field("grade_type"),
+                grade: field("grade").parse().unwrap_or_default(),
+                origin: meta.get("kind").cloned().unwrap_or_else(|| "school".to_string()),
+                revoked: field("revoked") == "true",
+            });
+            index += U256::one();
+        }
+        grades.sort_by(|a, b| (a.year, &a.subject, a.token_id).cmp(&(b.year, &b.subject, b.token_id)));
+
+        let subjects: BTreeSet<(u32, String)> = grades.iter().map(|grade| (grade.year, grade.subject.clone())).collect();
+        let mut averages = Vec::new();
+        for (year, subject) in subjects {
+            let key = keys::subject_year_key(&student, &subject, year);
+            let average = total::average(read_dictionary_item(state, keys::GRADE_TOTALS, &key)?.unwrap_or_default());
+            let final_average = read_dictionary_item(state, keys::FINAL_AVERAGES, &key)?;
+            averages.push(TranscriptAverage { subject, year, average, final_average });
+        }
+        let graduated = read_dictionary_item(state, keys::GRADUATES, &keys::key_to_str(&student))?;
+
+        Ok(Transcript { school, meta, student, grades, averages, graduated })
+    }
+
+    pub fn to_json(&self) -> String {
+        let grades: Vec<_> = self
+            .grades
+            .iter()
+            .map(|grade| {
+                json!({
+                    "token_id": grade.token_id.to_string(),
+                    "subject": grade.subject,
+                    "year": grade.year,
+                    "type": grade.grade_type,
+                    "grade": grade.grade,
+                    "origin": grade.origin,
+                    "revoked": grade.revoked,
+                })
+            })
+            .collect();
+        let averages: Vec<_> = self
+            .averages
+            .iter()
+            .map(|average| {
+                json!({
+                    "subject": average.subject,
+                    "year": average.year,
+                    "average": average.average,
+                    "final_average": average.final_average,
+                })
+            })
+            .collect();
+        let transcript = json!({
+            "school": self.school,
+            "meta": self.meta,
+            "student": self.student.to_formatted_string(),
+            "grades": grades,
+            "averages": averages,
+            "graduated": self.graduated,
+        });
+        serde_json::to_string_pretty(&transcript).expect("a transcript is valid JSON")
+    }
+
+    /// One row per grade, then one per average with the type `average` or `final average`.
+    pub fn to_csv(&self) -> String {
+        let mut csv = "year,subject,type,grade,origin,revoked,token_id\n".to_string();
+        for grade in &self.grades {
+            csv += &csv_row(&[
+                &grade.year.to_string(),
+                &grade.subject,
+                &grade.grade_type,
+                &format_grade(grade.grade),
+                &grade.origin,
+                &grade.revoked.to_string(),
+                &grade.token_id.to_string(),
+            ]);
+        }
+        for average in &self.averages {
+            for (grade_type, grade) in [("average", average.average), ("final average", average.final_average)] {
+                if let Some(grade) = grade {
+                    csv += &csv_row(&[&average.year.to_string(), &average.subject, grade_type, &format_grade(grade), "", "", ""]);
+                }
+            }
+        }
+        csv
+    }
+
+    /// The report card: school and its `meta`, the grades per year and the averages.
+    pub fn to_pdf(&self) -> Vec<u8> {
+        let mut lines = vec![Line::Heading(self.school.clone())];
+        for (field, value) in &self.meta {
+            lines.push(Line::Text(format!("{}: {}", field, value)));
+        }
+        lines.push(Line::Text(String::new()));
+        lines.push(Line::Heading("Report card".to_string()));
+        lines.push(Line::Text(format!("Student: {}", self.student.to_formatted_string())));
+        if let Some(year) = self.graduated {
+            lines.push(Line::Text(format!("Graduated: {}", year)));
+        }
+
+        let years: BTreeSet<u32> = self.grades.iter().map(|grade| grade.year).collect();
+        for year in years {
+            lines.push(Line::Text(String::new()));
+            lines.push(Line::Heading(format!("{}", year)));
+            lines.push(Line::Heading(format!("{:<24}{:<16}{:>6}  {}", "Subject", "Type", "Grade", "Remark")));
+            for grade in self.grades.iter().filter(|grade| grade.year == year) {
+                let remark = match (grade.revoked, grade.origin.as_str()) {
+                    (true, _) => "revoked",
+                    (false, "school") => "",
+                    (false, origin) => origin,
+                };
+                lines.push(Line::Text(format!(
+                    "{:<24}{:<16}{:>6}  {}",
+                    grade.subject,
+                    grade.grade_type,
+                    format_grade(grade.grade),
+                    remark
+                )));
+            }
+            for average in self.averages.iter().filter(|average| average.year == year) {
+                let grade = average.final_average.or(average.average).map(format_grade).unwrap_or_else(|| "-".to_string());
+                let label = if average.final_average.is_some() { "final average" } else { "average" };
+                lines.push(Line::Text(format!("{:<24}{:<16}{:>6}", average.subject, label, grade)));
+            }
+        }
+        pdf::document(&lines)
+    }
+}
+
+fn csv_row(fields: &[&str]) -> String {
+    let fields: Vec<String> = fields
+        .iter()
+        .map(|field| {
+            if field.contains(|c: char| c == ',' || c == '"' || c == '\n') {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect();
+    fields.join(",") + "\n"
+}
@@ -0,0 +1,290 @@
+//! A stand-in for a Casper node with a noten contract installed, running on the in-memory
+//! execution engine the tests use, so the client, CLI and indexer can be tried end to end
+//! without a network.
+//!
+//! It answers the JSON-RPC methods those need: `chain_get_state_root_hash`,
+//! `query_global_state`, `state_get_dictionary_item`, `account_put_deploy` and `info_get_deploy`.
+//! Deploys run as soon as they're put, one block each. Their approvals aren't checked, accounts
+//! are funded the first time they send a deploy, and the payment is always the standard payment,
+//! so it is no place to test signing or gas.
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::Path;
+use std::str::FromStr;
+
+use casper_engine_test_support::{
+    DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder, ARG_AMOUNT, DEFAULT_ACCOUNT_ADDR, DEFAULT_PAYMENT,
+    DEFAULT_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::engine_state::{self, ExecuteRequest};
+use casper_execution_engine::core::execution;
+use casper_hashing::Digest;
+use casper_types::account::AccountHash;
+use casper_types::bytesrepr::FromBytes;
+use casper_types::{
+    runtime_args, system::mint, CLTyped, ContractHash, ContractPackageHash, Key, PublicKey, RuntimeArgs, StoredValue, Timestamp, U512,
+};
+use cep47::Meta;
+use noten_client::{call, NotenError};
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Response, Server};
+
+const API_VERSION: &str = "1.4.4";
+const ACCOUNT_FUNDS: u64 = 50_000_000_000_000;
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_PARAMS: i64 = -32602;
+const METHOD_NOT_FOUND: i64 = -32601;
+const QUERY_FAILED: i64 = -32003;
+const DEPLOY_NOT_FOUND: i64 = -32000;
+
+type RpcResult = Result<Value, (i64, String)>;
+
+pub struct DevNode {
+    builder: InMemoryWasmTestBuilder,
+    contract: ContractHash,
+    package: ContractPackageHash,
+    /// Every state root hash the node went through, so reads at an older one still work.
+    state_roots: BTreeMap<String, Digest>,
+    deploys: BTreeMap<String, (Value, Value)>,
+}
+
+impl DevNode {
+    /// Runs genesis, funds `admin` and installs the noten contract in `wasm` with `meta` from it.
+    pub fn start(wasm: &Path, admin: &PublicKey, meta: Meta) -> Result<Self, String> {
+        let wasm = std::fs::read(wasm).map_err(|error| format!("{}: {}", wasm.display(), error))?;
+        let admin = admin.to_account_hash();
+
+        let mut builder = InMemoryWasmTestBuilder::default();
+        builder.run_genesis(&DEFAULT_RUN_GENESIS_REQUEST).commit();
+        builder.exec(fund_account(&admin)).expect_success().commit();
+        let install = DeployItemBuilder::new()
+            .with_empty_payment_bytes(runtime_args! {ARG_AMOUNT => *DEFAULT_PAYMENT})
+            .with_address(admin)
+            .with_authorization_keys(&[admin])
+            .with_session_bytes(wasm, call::install_args("noten", "NOT", meta, "noten"))
+            .with_deploy_hash(rand::random())
+            .build();
+        builder.exec(ExecuteRequestBuilder::from_deploy_item(install).build()).commit();
+        if let Some(error) = builder.get_error() {
+            return Err(format!("installing the contract failed: {}", error));
+        }
+
+        let contract = query(&builder, Key::Account(admin), "noten_contract_hash_wrapped")?;
+        let package = query(&builder, Key::Account(admin), "noten_package_hash_wrapped")?;
+        let mut node = DevNode { builder, contract, package, state_roots: BTreeMap::new(), deploys: BTreeMap::new() };
+        node.record_state_root();
+        Ok(node)
+    }
+
+    pub fn contract(&self) -> ContractHash {
+        self.contract
+    }
+
+    pub fn package(&self) -> ContractPackageHash {
+        self.package
+    }
+
+    pub fn builder(&self) -> &InMemoryWasmTestBuilder {
+        &self.builder
+    }
+
+    /// Answers a JSON-RPC request.
+    pub fn handle(&mut self, request: &Value) -> Value {
+        let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "chain_get_state_root_hash" => Ok(json!({ "api_version": API_VERSION, "state_root_hash": self.state_root_hash() })),
+            "query_global_state" => self.query_global_state(&params),
+            "state_get_dictionary_item" => self.state_get_dictionary_item(&params),
+            "account_put_deploy" => self.account_put_deploy(&params),
+            "info_get_deploy" => self.info_get_deploy(&params),
+            method => Err((METHOD_NOT_FOUND, format!("method {} not supported", method))),
+        };
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            Err((code, message)) => json!({ "jsonrpc": "2.0", "id": request["id"], "error": { "code": code, "message": message } }),
+        }
+    }
+
+    /// Answers the requests `server` receives at `/rpc` until it shuts down.
+    pub fn serve(&mut self, server: &Server) {
+        for mut request in server.incoming_requests() {
+            if request.method() != &Method::Post || request.url() != "/rpc" {
+                let _ = request.respond(Response::from_string("not found").with_status_code(404));
+                continue;
+            }
+            let mut body = String::new();
+            let response = match request.as_reader().read_to_string(&mut body).map_err(|error| error.to_string()) {
+                Ok(_) => match serde_json::from_str::<Value>(&body) {
+                    Ok(call) => self.handle(&call),
+                    Err(error) => parse_error(error.to_string()),
+                },
+                Err(error) => parse_error(error),
+            };
+            let header = Header::from_bytes("Content-Type", "application/json").expect("a valid header");
+            let _ = request.respond(Response::from_string(response.to_string()).with_header(header));
+        }
+    }
+
+    fn state_root_hash(&self) -> String {
+        hex::encode(self.builder.get_post_state_hash())
+    }
+
+    fn record_state_root(&mut self) {
+        self.state_roots.insert(self.state_root_hash(), self.builder.get_post_state_hash());
+    }
+
+    fn state_root(&self, hash: &Value) -> Result<Digest, (i64, String)> {
+        let hash = hash.as_str().ok_or((INVALID_PARAMS, "missing state root hash".to_string()))?;
+        self.state_roots.get(hash).copied().ok_or((QUERY_FAILED, format!("unknown state root hash {}", hash)))
+    }
+
+    fn query_global_state(&self, params: &Value) -> RpcResult {
+        let root = self.state_root(&params["state_identifier"]["StateRootHash"])?;
+        let key = parse_key(&params["key"])?;
+        let path: Vec<String> = serde_json::from_value(params["path"].clone()).unwrap_or_default();
+        let value = self.builder.query(Some(root), key, &path).map_err(|error| (QUERY_FAILED, error))?;
+        Ok(json!({ "api_version": API_VERSION, "stored_value": stored_value(&value)? }))
+    }
+
+    fn state_get_dictionary_item(&self, params: &Value) -> RpcResult {
+        let root = self.state_root(&params["state_root_hash"])?;
+        let identifier = &params["dictionary_identifier"]["ContractNamedKey"];
+        let contract = parse_key(&identifier["key"])?;
+        let dictionary = identifier["dictionary_name"].as_str().ok_or((INVALID_PARAMS, "missing dictionary_name".to_string()))?;
+        let item_key = identifier["dictionary_item_key"].as_str().ok_or((INVALID_PARAMS, "missing dictionary_item_key".to_string()))?;
+
+        let named_keys = match self.builder.query(Some(root), contract, &[]).map_err(|error| (QUERY_FAILED, error))? {
+            StoredValue::Contract(contract) => contract.named_keys().clone(),
+            StoredValue::Account(account) => account.named_keys().clone(),
+            _ => return Err((QUERY_FAILED, "the key is neither a contract nor an account".to_string())),
+        };
+        let uref = named_keys
+            .get(dictionary)
+            .and_then(Key::as_uref)
+            .ok_or((QUERY_FAILED, format!("no dictionary {}", dictionary)))?;
+        let dictionary_key = Key::dictionary(*uref, item_key.as_bytes());
+        let value = self.builder.query(Some(root), dictionary_key, &[]).map_err(|error| (QUERY_FAILED, error))?;
+        Ok(json!({
+            "api_version": API_VERSION,
+            "dictionary_key": dictionary_key.to_formatted_string(),
+            "stored_value": stored_value(&value)?,
+        }))
+    }
+
+    fn account_put_deploy(&mut self, params: &Value) -> RpcResult {
+        let deploy = &params["deploy"];
+        let hash = deploy["hash"].as_str().ok_or((INVALID_PARAMS, "missing deploy hash".to_string()))?.to_string();
+        let request = self.execute_request(deploy).map_err(|error| (INVALID_PARAMS, error))?;
+
+        self.builder.exec(request).commit();
+        let result = match self.builder.get_error() {
+            None => json!({ "Success": { "effect": {}, "transfers": [], "cost": "0" } }),
+            Some(error) => json!({ "Failure": { "effect": {}, "transfers": [], "cost": "0", "error_message": error_message(&error) } }),
+        };
+        self.record_state_root();
+        self.deploys.insert(hash.clone(), (deploy.clone(), result));
+        Ok(json!({ "api_version": API_VERSION, "deploy_hash": hash }))
+    }
+
+    fn info_get_deploy(&self, params: &Value) -> RpcResult {
+        let hash = params["deploy_hash"].as_str().ok_or((INVALID_PARAMS, "missing deploy_hash".to_string()))?;
+        let (deploy, result) = self.deploys.get(hash).ok_or((DEPLOY_NOT_FOUND, format!("no deploy {}", hash)))?;
+        Ok(json!({
+            "api_version": API_VERSION,
+            "deploy": deploy,
+            "execution_results": [{ "block_hash": self.state_root_hash(), "result": result }],
+        }))
+    }
+
+    /// The deploy as the engine runs it, funding its account first if this is its first deploy.
+    fn execute_request(&mut self, deploy: &Value) -> Result<ExecuteRequest, String> {
+        let header = &deploy["header"];
+        let account = header["account"]
+            .as_str()
+            .and_then(|account| PublicKey::from_hex(account).ok())
+            .ok_or("the deploy has no account")?
+            .to_account_hash();
+        let time = header["timestamp"]
+            .as_str()
+            .and_then(|time| Timestamp::from_str(time).ok())
+            .ok_or("the deploy has no timestamp")?
+            .millis();
+        let mut deploy_hash = [0u8; 32];
+        hex::decode_to_slice(deploy["hash"].as_str().unwrap_or_default(), &mut deploy_hash).map_err(|_| "malformed deploy hash")?;
+
+        let (hash, version, entry_point, args) = match deploy["session"].as_object().and_then(|session| session.iter().next()) {
+            Some((kind, session)) if kind == "StoredVersionedContractByHash" || kind == "StoredContractByHash" => {
+                let mut hash = [0u8; 32];
+                hex::decode_to_slice(session["hash"].as_str().unwrap_or_default(), &mut hash).map_err(|_| "malformed contract hash")?;
+                let entry_point = session["entry_point"].as_str().ok_or("the session has no entry point")?.to_string();
+                let args: RuntimeArgs = serde_json::from_value(session["args"].clone()).map_err(|error| error.to_string())?;
+                (hash, (kind == "StoredVersionedContractByHash").then(|| session["version"].as_u64()), entry_point, args)
+            }
+            _ => return Err("only calls to stored contracts by hash are supported".to_string()),
+        };
+
+        if self.builder.get_account(account).is_none() {
+            self.builder.exec(fund_account(&account)).expect_success().commit();
+        }
+        let deploy_item = DeployItemBuilder::new()
+            .with_empty_payment_bytes(runtime_args! {ARG_AMOUNT => *DEFAULT_PAYMENT})
+            .with_address(account)
+            .with_authorization_keys(&[account])
+            .with_deploy_hash(deploy_hash);
+        let deploy_item = match version {
+            Some(version) => deploy_item.with_stored_versioned_contract_by_hash(hash, version.map(|version| version as u32), &entry_point, args),
+            None => deploy_item.with_stored_session_hash(ContractHash::new(hash), &entry_point, args),
+        };
+        Ok(ExecuteRequestBuilder::from_deploy_item(deploy_item.build()).with_block_time(time).build())
+    }
+}
+
+fn fund_account(account: &AccountHash) -> ExecuteRequest {
+    let deploy_item = DeployItemBuilder::new()
+        .with_address(*DEFAULT_ACCOUNT_ADDR)
+        .with_authorization_keys(&[*DEFAULT_ACCOUNT_ADDR])
+        .with_empty_payment_bytes(runtime_args! {ARG_AMOUNT => *DEFAULT_PAYMENT})
+        .with_transfer_args(runtime_args! {
+            mint::ARG_AMOUNT => U512::from(ACCOUNT_FUNDS),
+            mint::ARG_TARGET => *account,
+            mint::ARG_ID => <Option::<u64>>::None
+        })
+        .with_deploy_hash(rand::random())
+        .build();
+    ExecuteRequestBuilder::from_deploy_item(deploy_item).build()
+}
+
+fn query<T: CLTyped + FromBytes>(builder: &InMemoryWasmTestBuilder, base: Key, name: &str) -> Result<T, String> {
+    builder
+        .query(None, base, &[name.to_string()])?
+        .as_cl_value()
+        .and_then(|value| value.clone().into_t().ok())
+        .ok_or_else(|| format!("no {} after installing the contract", name))
+}
+
+fn parse_error(message: String) -> Value {
+    json!({ "jsonrpc": "2.0", "id": null, "error": { "code": PARSE_ERROR, "message": message } })
+}
+
+fn parse_key(key: &Value) -> Result<Key, (i64, String)> {
+    key.as_str()
+        .and_then(|key| Key::from_formatted_str(key).ok())
+        .ok_or((INVALID_PARAMS, "expected a formatted key".to_string()))
+}
+
+fn stored_value(value: &StoredValue) -> RpcResult {
+    serde_json::to_value(value).map_err(|error| (QUERY_FAILED, error.to_string()))
+}
+
+/// The engine's error, with the name of the contract's error if it reverted with one.
+fn error_message(error: &engine_state::Error) -> String {
+    match error {
+        engine_state::Error::Exec(execution::Error::Revert(api_error)) => match NotenError::from_api_error(*api_error) {
+            Some(noten_error) => format!("{} ({:?})", error, noten_error),
+            None => error.to_string(),
+        },
+        _ => error.to_string(),
+    }
+}
@@ -0,0 +1,44 @@
+use std::path::Path;
+use std::{env, process};
+
+use casper_types::PublicKey;
+use cep47::Meta;
+use noten_devnode::DevNode;
+use tiny_http::Server;
+
+const USAGE: &str = "\
+usage:
+  noten-devnode --wasm <noten.wasm> --admin <public key> [--school <name>] [--kanton <code>]
+                [--port <port>]
+
+Installs the contract in <noten.wasm> from the account of <public key>, given as hex, and serves
+the JSON-RPC methods the client, CLI and indexer use at http://localhost:<port>/rpc, 7777 by
+default. Nothing is kept once it stops.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let flag = |name: &str| args.iter().position(|arg| arg == name).and_then(|position| args.get(position + 1));
+    let required = |name: &str| flag(name).ok_or_else(|| format!("missing {}\n\n{}", name, USAGE));
+
+    let admin = PublicKey::from_hex(required("--admin")?).map_err(|_| "--admin has to be a hex public key".to_string())?;
+    let mut meta = Meta::new();
+    meta.insert("school".to_string(), flag("--school").cloned().unwrap_or_else(|| "Devnode".to_string()));
+    meta.insert("kanton".to_string(), flag("--kanton").cloned().unwrap_or_else(|| "ZG".to_string()));
+    let port = flag("--port").map_or(Ok(7777), |port| port.parse::<u16>().map_err(|_| format!("'{}' is not a port", port)))?;
+
+    let mut node = DevNode::start(Path::new(required("--wasm")?), &admin, meta)?;
+    let server = Server::http(("127.0.0.1", port)).map_err(|error| error.to_string())?;
+    println!("contract hash: {}", node.contract().to_formatted_string());
+    println!("package hash:  {}", node.package().to_formatted_string());
+    println!("listening on http://127.0.0.1:{}/rpc", port);
+
+    node.serve(&server);
+    Ok(())
+}
@@ -0,0 +1,498 @@
+//! Mirrors a noten contract into SQLite, so dashboards can query students, teachers, subjects,
+//! grades and averages without reading a dictionary item per value.
+//!
+//! The mirror follows the contract's two logs: the grade events (`events`) and the staff log
+//! (`staff_events`). Every entry names the grade or account it changed, and the indexer re-reads
+//! that grade or account from state, so the mirror never has to replay the contract's rules.
+//! Closing a year logs every student it promotes or graduates, whose final averages are re-read
+//! with their class.
+//! Teachers added before the staff log existed (schema version 4) are not known to the mirror.
+
+use std::fmt;
+
+use casper_types::bytesrepr;
+use casper_types::{Key, U256};
+use cep47::{Meta, TokenId};
+use noten_client::appointment::Appointment;
+use noten_client::keys;
+use noten_client::record::GradeRecord;
+use noten_client::state::{read_dictionary_item, read_named_key};
+use noten_client::total::{self, GradeTotal};
+use noten_client::StateReader;
+use rusqlite::{params, Connection, OptionalExtension};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS cursors (
+    log TEXT PRIMARY KEY,
+    next_index INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS teachers (
+    account TEXT PRIMARY KEY,
+    active INTEGER NOT NULL,
+    appointment_start INTEGER,
+    appointment_end INTEGER,
+    subjects TEXT,
+    classes TEXT
+);
+CREATE TABLE IF NOT EXISTS students (
+    account TEXT PRIMARY KEY,
+    class TEXT,
+    graduated INTEGER
+);
+CREATE TABLE IF NOT EXISTS subjects (
+    name TEXT PRIMARY KEY
+);
+CREATE TABLE IF NOT EXISTS grades (
+    token_id TEXT PRIMARY KEY,
+    student TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    grade_type TEXT NOT NULL,
+    grade INTEGER NOT NULL,
+    teacher TEXT NOT NULL,
+    issued_at INTEGER NOT NULL,
+    origin TEXT NOT NULL,
+    revoked INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS averages (
+    student TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    year INTEGER NOT NULL,
+    sum INTEGER NOT NULL,
+    count INTEGER NOT NULL,
+    average INTEGER,
+    final_average INTEGER,
+    PRIMARY KEY (student, subject, year)
+);
+";
+
+/// Staff log entries about a student rather than a teacher.
+const STUDENT_EVENTS: [&str; 2] = ["class_set", "graduated"];
+
+#[derive(Debug)]
+pub enum IndexerError {
+    Sql(rusqlite::Error),
+    State(bytesrepr::Error),
+    /// A log entry the contract's count says exists could not be read.
+    MissingEntry { log: &'static str, index: u64 },
+    MalformedEntry { log: &'static str, index: u64 },
+}
+
+impl fmt::Display for IndexerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexerError::Sql(error) => write!(f, "database: {}", error),
+            IndexerError::State(error) => write!(f, "could not decode the contract's state: {}", error),
+            IndexerError::MissingEntry { log, index } => write!(f, "entry {} of {} could not be read", index, log),
+            IndexerError::MalformedEntry { log, index } => write!(f, "entry {} of {} is malformed", index, log),
+        }
+    }
+}
+
+impl From<rusqlite::Error> for IndexerError {
+    fn from(error: rusqlite::Error) -> Self {
+        IndexerError::Sql(error)
+    }
+}
+
+impl From<bytesrepr::Error> for IndexerError {
+    fn from(error: bytesrepr::Error) -> Self {
+        IndexerError::State(error)
+    }
+}
+
+/// A row that differs between the mirror and the contract's state; `None` where one side has no row.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub table: &'static str,
+    pub key: String,
+    pub mirror: Option<String>,
+    pub state: Option<String>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |row: &Option<String>| row.clone().unwrap_or_else(|| "missing".to_string());
+        write!(f, "{} {}: mirror {}, state {}", self.table, self.key, side(&self.mirror), side(&self.state))
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct GradeRow {
+    student: String,
+    subject: String,
+    year: u32,
+    grade_type: String,
+    grade: u32,
+    teacher: String,
+    issued_at: i64,
+    origin: String,
+    revoked: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct TeacherRow {
+    active: bool,
+    appointment: Option<(i64, i64, String, String)>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct StudentRow {
+    class: Option<String>,
+    graduated: Option<u32>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct AverageRow {
+    sum: i64,
+    count: u32,
+    average: Option<u32>,
+    final_average: Option<u32>,
+}
+
+pub struct Indexer {
+    connection: Connection,
+}
+
+impl Indexer {
+    pub fn open(path: &str) -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    pub fn open_in_memory() -> Result<Self, IndexerError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(connection: Connection) -> Result<Self, IndexerError> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Indexer { connection })
+    }
+
+    /// The mirror, for queries.
+    pub fn connection(&self) -> &Connection {
+        &self.connection
+    }
+
+    /// Applies the log entries written since the last sync, in one transaction, and returns
+    /// how many there were.
+    pub fn sync<R: StateReader>(&mut self, state: &R) -> Result<u64, IndexerError> {
+        let tx = self.connection.transaction()?;
+        let mut applied = 0;
+
+        for (index, entry) in new_entries(&tx, state, "event_count", keys::EVENTS)? {
+            let token_id = entry
+                .get("token_id")
+                .and_then(|token_id| U256::from_dec_str(token_id).ok())
+                .ok_or(IndexerError::MalformedEntry { log: keys::EVENTS, index })?;
+            refresh_grade(&tx, state, token_id)?;
+            applied += 1;
+        }
+        for (index, entry) in new_entries(&tx, state, "staff_event_count", keys::STAFF_EVENTS)? {
+            let account = entry
+                .get("account")
+                .and_then(|account| Key::from_formatted_str(account).ok())
+                .ok_or(IndexerError::MalformedEntry { log: keys::STAFF_EVENTS, index })?;
+            match entry.get("event").map(String::as_str) {
+                Some(event) if STUDENT_EVENTS.contains(&event) => {
+                    refresh_student(&tx, state, account)?;
+                    // A closed year also fixes the student's final averages.
+                    let averages: Vec<(String, u32)> = tx
+                        .prepare("SELECT subject, year FROM averages WHERE student = ?1")?
+                        .query_map([account.to_formatted_string()], |row| Ok((row.get(0)?, row.get(1)?)))?
+                        .collect::<Result<_, _>>()?;
+                    for (subject, year) in averages {
+                        refresh_average(&tx, state, &account, &subject, year)?;
+                    }
+                }
+                _ => refresh_teacher(&tx, state, account)?,
+            }
+            applied += 1;
+        }
+
+        tx.commit()?;
+        Ok(applied)
+    }
+
+    /// Compares every row of the mirror, and every grade in the contract's grade index, with state.
+    pub fn check<R: StateReader>(&self, state: &R) -> Result<Vec<Mismatch>, IndexerError> {
+        let mut mismatches = Vec::new();
+        let conn = &self.connection;
+
+        let grade_count: u64 = read_named_key(state, "grade_count")?.unwrap_or_default();
+        let mut indexed = Vec::new();
+        for position in 0..grade_count {
+            let token_id: TokenId = read_dictionary_item(state, keys::GRADES, &position.to_string())?
+                .ok_or(IndexerError::MissingEntry { log: keys::GRADES, index: position })?;
+            indexed.push(token_id.to_string());
+        }
+        let mut mirrored: Vec<String> = conn.prepare("SELECT token_id FROM grades")?.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?;
+        mirrored.retain(|token_id| !indexed.contains(token_id));
+        for token_id in indexed.iter().chain(&mirrored) {
+            let expected = grade_row(state, U256::from_dec_str(token_id).unwrap_or_default())?;
+            compare(&mut mismatches, "grades", token_id, mirrored_grade(conn, token_id)?, expected);
+        }
+
+        // Accounts the staff log names are checked too, so ones missing from the mirror show up.
+        let (logged_teachers, logged_students) = logged_accounts(state)?;
+        for account in merged(accounts(conn, "SELECT account FROM teachers")?, logged_teachers) {
+            compare(&mut mismatches, "teachers", &account, mirrored_teacher(conn, &account)?, Some(teacher_row(state, &parse_key(&account))?));
+        }
+        for account in merged(accounts(conn, "SELECT account FROM students")?, logged_students) {
+            compare(&mut mismatches, "students", &account, mirrored_student(conn, &account)?, Some(student_row(state, &parse_key(&account))?));
+        }
+        let averages: Vec<(String, String, u32)> = conn
+            .prepare("SELECT student, subject, year FROM averages")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+            .collect::<Result<_, _>>()?;
+        for (student, subject, year) in averages {
+            let key = format!("{} {} {}", student, subject, year);
+            let expected = average_row(state, &parse_key(&student), &subject, year)?;
+            compare(&mut mismatches, "averages", &key, mirrored_average(conn, &student, &subject, year)?, Some(expected));
+        }
+        Ok(mismatches)
+    }
+}
+
+fn compare<T: fmt::Debug + PartialEq>(mismatches: &mut Vec<Mismatch>, table: &'static str, key: &str, mirror: Option<T>, state: Option<T>) {
+    if mirror != state {
+        mismatches.push(Mismatch {
+            table,
+            key: key.to_string(),
+            mirror: mirror.map(|row| format!("{:?}", row)),
+            state: state.map(|row| format!("{:?}", row)),
+        });
+    }
+}
+
+/// Entries of a log from the mirror's cursor up to the contract's count, advancing the cursor.
+fn new_entries<R: StateReader>(conn: &Connection, state: &R, count_key: &str, log: &'static str) -> Result<Vec<(u64, Meta)>, IndexerError> {
+    let count: u64 = read_named_key(state, count_key)?.unwrap_or_default();
+    let next: i64 = conn
+        .query_row("SELECT next_index FROM cursors WHERE log = ?1", [log], |row| row.get(0))
+        .optional()?
+        .unwrap_or_default();
+    let mut entries = Vec::new();
+    for index in next as u64..count {
+        let entry = read_dictionary_item(state, log, &index.to_string())?.ok_or(IndexerError::MissingEntry { log, index })?;
+        entries.push((index, entry));
+    }
+    conn.execute(
+        "INSERT INTO cursors (log, next_index) VALUES (?1, ?2) ON CONFLICT (log) DO UPDATE SET next_index = ?2",
+        params![log, count.max(next as u64) as i64],
+    )?;
+    Ok(entries)
+}
+
+fn grade_row<R: StateReader>(state: &R, token_id: TokenId) -> Result<Option<GradeRow>, IndexerError> {
+    let item_key = keys::token_key(token_id);
+    let record: GradeRecord = match read_dictionary_item(state, keys::GRADE_RECORDS, &item_key)? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    let meta: Meta = read_dictionary_item(state, keys::METADATA, &item_key)?.unwrap_or_default();
+    Ok(Some(GradeRow {
+        student: record.student.to_formatted_string(),
+        subject: record.subject,
+        year: record.year,
+        grade_type: record.grade_type,
+        grade: record.grade,
+        teacher: record.teacher.to_formatted_string(),
+        issued_at: record.issued_at as i64,
+        origin: meta.get("kind").cloned().unwrap_or_else(|| "school".to_string()),
+        revoked: read_dictionary_item(state, keys::REVOKED_GRADES, &item_key)?.unwrap_or_default(),
+    }))
+}
+
+fn teacher_row<R: StateReader>(state: &R, account: &Key) -> Result<TeacherRow, IndexerError> {
+    let item_key = keys::key_to_str(account);
+    let appointment: Option<Appointment> = read_dictionary_item(state, keys::APPOINTMENTS, &item_key)?;
+    Ok(TeacherRow {
+        active: read_dictionary_item(state, keys::ADMINS, &item_key)?.unwrap_or_default(),
+        appointment: appointment.map(|appointment| {
+            (appointment.start as i64, appointment.end as i64, appointment.subjects.join(","), appointment.classes.join(","))
+        }),
+    })
+}
+
+fn student_row<R: StateReader>(state: &R, account: &Key) -> Result<StudentRow, IndexerError> {
+    let item_key = keys::key_to_str(account);
+    Ok(StudentRow {
+        class: read_dictionary_item(state, keys::STUDENT_CLASSES, &item_key)?,
+        graduated: read_dictionary_item(state, keys::GRADUATES, &item_key)?,
+    })
+}
+
+fn average_row<R: StateReader>(state: &R, student: &Key, subject: &str, year: u32) -> Result<AverageRow, IndexerError> {
+    let item_key = keys::subject_year_key(student, subject, year);
+    let (sum, count): GradeTotal = read_dictionary_item(state, keys::GRADE_TOTALS, &item_key)?.unwrap_or_default();
+    Ok(AverageRow {
+        sum: sum as i64,
+        count,
+        average: total::average((sum, count)),
+        final_average: read_dictionary_item(state, keys::FINAL_AVERAGES, &item_key)?,
+    })
+}
+
+fn refresh_grade<R: StateReader>(conn: &Connection, state: &R, token_id: TokenId) -> Result<(), IndexerError> {
+    let row = match grade_row(state, token_id)? {
+        Some(row) => row,
+        None => {
+            conn.execute("DELETE FROM grades WHERE token_id = ?1", [token_id.to_string()])?;
+            return Ok(());
+        }
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO grades (token_id, student, subject, year, grade_type, grade, teacher, issued_at, origin, revoked)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            token_id.to_string(),
+            row.student,
+            row.subject,
+            row.year,
+            row.grade_type,
+            row.grade,
+            row.teacher,
+            row.issued_at,
+            row.origin,
+            row.revoked
+        ],
+    )?;
+    conn.execute("INSERT OR IGNORE INTO subjects (name) VALUES (?1)", [&row.subject])?;
+    let student = parse_key(&row.student);
+    refresh_student(conn, state, student)?;
+    refresh_average(conn, state, &student, &row.subject, row.year)
+}
+
+fn refresh_teacher<R: StateReader>(conn: &Connection, state: &R, account: Key) -> Result<(), IndexerError> {
+    let row = teacher_row(state, &account)?;
+    let (start, end, subjects, classes) = match row.appointment {
+        Some((start, end, subjects, classes)) => (Some(start), Some(end), Some(subjects), Some(classes)),
+        None => (None, None, None, None),
+    };
+    conn.execute(
+        "INSERT OR REPLACE INTO teachers (account, active, appointment_start, appointment_end, subjects, classes)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![account.to_formatted_string(), row.active, start, end, subjects, classes],
+    )?;
+    Ok(())
+}
+
+fn refresh_student<R: StateReader>(conn: &Connection, state: &R, account: Key) -> Result<(), IndexerError> {
+    let row = student_row(state, &account)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO students (account, class, graduated) VALUES (?1, ?2, ?3)",
+        params![account.to_formatted_string(), row.class, row.graduated],
+    )?;
+    Ok(())
+}
+
+fn refresh_average<R: StateReader>(conn: &Connection, state: &R, student: &Key, subject: &str, year: u32) -> Result<(), IndexerError> {
+    let row = average_row(state, student, subject, year)?;
+    conn.execute(
+        "INSERT OR REPLACE INTO averages (student, subject, year, sum, count, average, final_average)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![student.to_formatted_string(), subject, year, row.sum, row.count, row.average, row.final_average],
+    )?;
+    Ok(())
+}
+
+fn mirrored_grade(conn: &Connection, token_id: &str) -> Result<Option<GradeRow>, IndexerError> {
+    Ok(conn
+        .query_row(
+            "SELECT student, subject, year, grade_type, grade, teacher, issued_at, origin, revoked FROM grades WHERE token_id = ?1",
+            [token_id],
+            |row| {
+                Ok(GradeRow {
+                    student: row.get(0)?,
+                    subject: row.get(1)?,
+                    year: row.get(2)?,
+                    grade_type: row.get(3)?,
+                    grade: row.get(4)?,
+                    teacher: row.get(5)?,
+                    issued_at: row.get(6)?,
+                    origin: row.get(7)?,
+                    revoked: row.get(8)?,
+                })
+            },
+        )
+        .optional()?)
+}
+
+fn mirrored_teacher(conn: &Connection, account: &str) -> Result<Option<TeacherRow>, IndexerError> {
+    Ok(conn
+        .query_row(
+            "SELECT active, appointment_start, appointment_end, subjects, classes FROM teachers WHERE account = ?1",
+            [account],
+            |row| {
+                let start: Option<i64> = row.get(1)?;
+                let end: Option<i64> = row.get(2)?;
+                let subjects: Option<String> = row.get(3)?;
+                let classes: Option<String> = row.get(4)?;
+                Ok(TeacherRow {
+                    active: row.get(0)?,
+                    appointment: match (start, end, subjects, classes) {
+                        (Some(start), Some(end), Some(subjects), Some(classes)) => Some((start, end, subjects, classes)),
+                        _ => None,
+                    },
+                })
+            },
+        )
+        .optional()?)
+}
+
+fn mirrored_student(conn: &Connection, account: &str) -> Result<Option<StudentRow>, IndexerError> {
+    Ok(conn
+        .query_row("SELECT class, graduated FROM students WHERE account = ?1", [account], |row| {
+            Ok(StudentRow { class: row.get(0)?, graduated: row.get(1)? })
+        })
+        .optional()?)
+}
+
+fn mirrored_average(conn: &Connection, student: &str, subject: &str, year: u32) -> Result<Option<AverageRow>, IndexerError> {
+    Ok(conn
+        .query_row(
+            "SELECT sum, count, average, final_average FROM averages WHERE student = ?1 AND subject = ?2 AND year = ?3",
+            params![student, subject, year],
+            |row| Ok(AverageRow { sum: row.get(0)?, count: row.get(1)?, average: row.get(2)?, final_average: row.get(3)? }),
+        )
+        .optional()?)
+}
+
+fn accounts(conn: &Connection, query: &str) -> Result<Vec<String>, IndexerError> {
+    Ok(conn.prepare(query)?.query_map([], |row| row.get(0))?.collect::<Result<_, _>>()?)
+}
+
+/// The teachers and the students named in the contract's staff log, formatted like the mirror's.
+fn logged_accounts<R: StateReader>(state: &R) -> Result<(Vec<String>, Vec<String>), IndexerError> {
+    let count: u64 = read_named_key(state, "staff_event_count")?.unwrap_or_default();
+    let (mut teachers, mut students) = (Vec::new(), Vec::new());
+    for index in 0..count {
+        let log = keys::STAFF_EVENTS;
+        let entry: Meta = read_dictionary_item(state, log, &index.to_string())?.ok_or(IndexerError::MissingEntry { log, index })?;
+        let account = entry
+            .get("account")
+            .and_then(|account| Key::from_formatted_str(account).ok())
+            .ok_or(IndexerError::MalformedEntry { log, index })?
+            .to_formatted_string();
+        let accounts = match entry.get("event") {
+            Some(event) if STUDENT_EVENTS.contains(&event.as_str()) => &mut students,
+            _ => &mut teachers,
+        };
+        if !accounts.contains(&account) {
+            accounts.push(account);
+        }
+    }
+    Ok((teachers, students))
+}
+
+/// `mirrored` followed by the accounts of `logged` the mirror doesn't have.
+fn merged(mut mirrored: Vec<String>, logged: Vec<String>) -> Vec<String> {
+    let missing: Vec<String> = logged.into_iter().filter(|account| !mirrored.contains(account)).collect();
+    mirrored.extend(missing);
+    mirrored
+}
+
+/// Accounts are stored formatted, as written by the indexer itself.
+fn parse_key(key: &str) -> Key {
+    Key::from_formatted_str(key).expect("the mirror stores formatted keys")
+}
@@ -0,0 +1,57 @@
+use std::{env, process};
+
+use casper_types::Key;
+use noten_client::rpc::RpcState;
+use noten_indexer::Indexer;
+
+const USAGE: &str = "\
+usage:
+  noten-indexer sync --node <url> --contract <hash> --db <file>
+  noten-indexer check --node <url> --contract <hash> --db <file>
+
+sync applies what the contract logged since the last sync to the SQLite mirror in <file>, check
+compares the mirror with the contract's state and fails if they differ. <hash> is the contract
+hash, formatted as hash-..., and <url> the node's RPC address, e.g. http://localhost:7777/rpc.";
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(error) = run(&args) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let (command, flags) = args.split_first().ok_or(USAGE)?;
+    let flag = |name: &str| {
+        flags
+            .iter()
+            .position(|flag| flag == name)
+            .and_then(|position| flags.get(position + 1))
+            .ok_or_else(|| format!("missing {}\n\n{}", name, USAGE))
+    };
+    let contract = Key::from_formatted_str(flag("--contract")?).map_err(|_| "--contract has to be a hash-... key".to_string())?;
+    let state = RpcState::connect(flag("--node")?, contract).map_err(|error| error.to_string())?;
+    let mut indexer = Indexer::open(flag("--db")?).map_err(|error| error.to_string())?;
+
+    match command.as_str() {
+        "sync" => {
+            let applied = indexer.sync(&state).map_err(|error| error.to_string())?;
+            println!("applied {} log entries at state root hash {}", applied, state.state_root_hash());
+            Ok(())
+        }
+        "check" => {
+            let mismatches = indexer.check(&state).map_err(|error| error.to_string())?;
+            for mismatch in &mismatches {
+                println!("{}", mismatch);
+            }
+            if mismatches.is_empty() {
+                println!("the mirror matches state root hash {}", state.state_root_hash());
+                Ok(())
+            } else {
+                Err(format!("{} rows differ from state root hash {}", mismatches.len(), state.state_root_hash()))
+            }
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
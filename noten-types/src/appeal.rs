@@ -0,0 +1,81 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped, Key};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+
+/// Where an appeal against a grade stands. Only `Open` and `UnderReview` can still change.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppealStatus {
+    Open = 0,
+    UnderReview = 1,
+    Upheld = 2,
+    Rejected = 3,
+}
+
+impl AppealStatus {
+    pub fn is_pending(self) -> bool {
+        matches!(self, AppealStatus::Open | AppealStatus::UnderReview)
+    }
+}
+
+impl ToBytes for AppealStatus {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        (*self as u8).to_bytes()
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+    }
+}
+
+impl FromBytes for AppealStatus {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        let status = match tag {
+            0 => AppealStatus::Open,
+            1 => AppealStatus::UnderReview,
+            2 => AppealStatus::Upheld,
+            3 => AppealStatus::Rejected,
+            _ => return Err(bytesrepr::Error::Formatting),
+        };
+        Ok((status, rem))
+    }
+}
+
+/// A dispute of a grade, filed by the student or one of their guardians.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Appeal {
+    pub appellant: Key,
+    pub reason: String,
+    pub status: AppealStatus,
+}
+
+impl CLTyped for Appeal {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Appeal {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.appellant.to_bytes()?);
+        result.append(&mut self.reason.to_bytes()?);
+        result.append(&mut self.status.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.appellant.serialized_length() + self.reason.serialized_length() + self.status.serialized_length()
+    }
+}
+
+impl FromBytes for Appeal {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (appellant, rem) = Key::from_bytes(bytes)?;
+        let (reason, rem) = String::from_bytes(rem)?;
+        let (status, rem) = AppealStatus::from_bytes(rem)?;
+        Ok((Appeal { appellant, reason, status }, rem))
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+/// A teacher's time-limited appointment, e.g. for a substitute. Block times are in milliseconds,
+/// empty `subjects` or `classes` mean the appointment isn't restricted to particular ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Appointment {
+    pub start: u64,
+    pub end: u64,
+    pub subjects: Vec<String>,
+    pub classes: Vec<String>,
+}
+
+impl Appointment {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.start <= now && now < self.end
+    }
+
+    pub fn covers_subject(&self, subject: &str) -> bool {
+        self.subjects.is_empty() || self.subjects.iter().any(|scoped| scoped == subject)
+    }
+
+    pub fn covers_class(&self, class: Option<&str>) -> bool {
+        self.classes.is_empty() || class.map_or(false, |class| self.classes.iter().any(|scoped| scoped == class))
+    }
+}
+
+impl CLTyped for Appointment {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Appointment {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.start.to_bytes()?);
+        result.append(&mut self.end.to_bytes()?);
+        result.append(&mut self.subjects.to_bytes()?);
+        result.append(&mut self.classes.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.start.serialized_length()
+            + self.end.serialized_length()
+            + self.subjects.serialized_length()
+            + self.classes.serialized_length()
+    }
+}
+
+impl FromBytes for Appointment {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (start, rem) = u64::from_bytes(bytes)?;
+        let (end, rem) = u64::from_bytes(rem)?;
+        let (subjects, rem) = Vec::<String>::from_bytes(rem)?;
+        let (classes, rem) = Vec::<String>::from_bytes(rem)?;
+        Ok((Appointment { start, end, subjects, classes }, rem))
+    }
+}
@@ -0,0 +1,159 @@
+use casper_types::ApiError;
+
+/// Errors the noten contract reverts with, reported as `ApiError::User` codes.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotenError {
+    /// The caller is not allowed to issue grades (same code `AdminControl` uses).
+    NotTeacher = 20,
+    /// No grade token exists for the given token id.
+    UnknownGrade = 21,
+    /// An encrypted payload must always be readable by the student and their guardians.
+    MissingRecipient = 22,
+    /// Pseudonyms are school-assigned hashes and must be `Key::Hash` keys.
+    InvalidPseudonym = 23,
+    /// The pseudonym is already bound to an account.
+    PseudonymClaimed = 24,
+    /// The claim does not open the commitment registered for the pseudonym.
+    InvalidClaim = 25,
+    /// The caller is not a registered guardian of the grade's student.
+    NotGuardian = 26,
+    /// The grade token belongs to a different student.
+    StudentMismatch = 27,
+    /// Only the student or their guardians can appeal a grade.
+    NotAppellant = 28,
+    /// The grade already has an appeal that is open or under review.
+    AppealPending = 29,
+    /// The appeal is missing or not in a state that allows this step.
+    InvalidAppealState = 30,
+    /// Only administrators and the review board can advance appeals.
+    NotReviewer = 31,
+    /// The action needs approval by several administrators, propose it instead.
+    ApprovalRequired = 32,
+    /// No proposal exists for the given id.
+    UnknownProposal = 33,
+    /// The proposal's deadline has passed.
+    ProposalExpired = 34,
+    /// The proposal has already been executed.
+    ProposalExecuted = 35,
+    /// The caller has already approved the proposal.
+    AlreadyApproved = 36,
+    /// The approval threshold has to be at least one and at most the number of permanent teachers,
+    /// and removing a teacher can't leave fewer permanent teachers than the threshold.
+    InvalidThreshold = 37,
+    /// Revoked grades can no longer be changed.
+    GradeRevoked = 38,
+    /// The teacher's appointment doesn't cover this subject or the student's class.
+    OutsideAppointment = 39,
+    /// An appointment has to end after it starts.
+    InvalidAppointment = 40,
+    /// A grade's `Meta` can't be read as a grade record.
+    MalformedGrade = 41,
+    /// Grading and staff changes are paused.
+    Paused = 42,
+    /// The grade's school year has been closed by the year rollover.
+    TermClosed = 43,
+    /// A rollover for a different school year hasn't finished yet.
+    RolloverInProgress = 44,
+    /// No transfer record exists for the given id.
+    UnknownTransfer = 45,
+    /// The transfer record is addressed to a different school.
+    WrongDestination = 46,
+    /// The transfer record has already been imported.
+    TransferImported = 47,
+    /// Grades from other schools are read-only.
+    ExternalGrade = 48,
+    /// Schools are identified by their contract package hash, a `Key::Hash`.
+    InvalidSchool = 49,
+    /// No exam exists for the given id.
+    UnknownExam = 50,
+    /// The student isn't in the class that sat the exam.
+    WrongClass = 51,
+    /// The grade isn't one of the grading scale's steps.
+    GradeOutOfScale = 52,
+    /// A grading scale needs `min < max`, a step that divides the range and at most `MAX_STEPS` grades.
+    InvalidScale = 53,
+    /// The grading scale can't change once grades have been given.
+    ScaleInUse = 54,
+    /// Only teachers without an appointment can change the staff, revoke grades, decide proposals,
+    /// change the grading scale or close a school year.
+    NotPermanentTeacher = 55,
+    /// Packages installed without their access uref can't get new versions; they're installed anew
+    /// and their grades imported with `import_legacy_grade`.
+    LegacyPackage = 56,
+    /// The school year has already been rolled over; its students were promoted once.
+    YearArchived = 57,
+    /// A rollover has to work through at least one student per call.
+    InvalidLimit = 58,
+    /// Legacy grades can only be imported before the first grade is given.
+    LegacyImportClosed = 59,
+    /// Transfers are only imported from active schools of the registry set with `set_registry`.
+    UnregisteredSchool = 60,
+    /// Teachers can't decide appeals against the grades they gave.
+    OwnGrade = 61,
+}
+
+impl From<NotenError> for ApiError {
+    fn from(error: NotenError) -> ApiError {
+        ApiError::User(error as u16)
+    }
+}
+
+impl NotenError {
+    /// The error for an `ApiError::User` code, if the contract reverts with that code.
+    pub fn from_code(code: u16) -> Option<NotenError> {
+        let error = match code {
+            20 => NotenError::NotTeacher,
+            21 => NotenError::UnknownGrade,
+            22 => NotenError::MissingRecipient,
+            23 => NotenError::InvalidPseudonym,
+            24 => NotenError::PseudonymClaimed,
+            25 => NotenError::InvalidClaim,
+            26 => NotenError::NotGuardian,
+            27 => NotenError::StudentMismatch,
+            28 => NotenError::NotAppellant,
+            29 => NotenError::AppealPending,
+            30 => NotenError::InvalidAppealState,
+            31 => NotenError::NotReviewer,
+            32 => NotenError::ApprovalRequired,
+            33 => NotenError::UnknownProposal,
+            34 => NotenError::ProposalExpired,
+            35 => NotenError::ProposalExecuted,
+            36 => NotenError::AlreadyApproved,
+            37 => NotenError::InvalidThreshold,
+            38 => NotenError::GradeRevoked,
+            39 => NotenError::OutsideAppointment,
+            40 => NotenError::InvalidAppointment,
+            41 => NotenError::MalformedGrade,
+            42 => NotenError::Paused,
+            43 => NotenError::TermClosed,
+            44 => NotenError::RolloverInProgress,
+            45 => NotenError::UnknownTransfer,
+            46 => NotenError::WrongDestination,
+            47 => NotenError::TransferImported,
+            48 => NotenError::ExternalGrade,
+            49 => NotenError::InvalidSchool,
+            50 => NotenError::UnknownExam,
+            51 => NotenError::WrongClass,
+            52 => NotenError::GradeOutOfScale,
+            53 => NotenError::InvalidScale,
+            54 => NotenError::ScaleInUse,
+            55 => NotenError::NotPermanentTeacher,
+            56 => NotenError::LegacyPackage,
+            57 => NotenError::YearArchived,
+            58 => NotenError::InvalidLimit,
+            59 => NotenError::LegacyImportClosed,
+            60 => NotenError::UnregisteredSchool,
+            61 => NotenError::OwnGrade,
+            _ => return None,
+        };
+        Some(error)
+    }
+
+    pub fn from_api_error(error: ApiError) -> Option<NotenError> {
+        match error {
+            ApiError::User(code) => Self::from_code(code),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+use crate::scale::GradingScale;
+use crate::total::average;
+
+/// An exam a class sits, which its grades are linked to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Exam {
+    pub subject: String,
+    pub class: String,
+    pub year: u32,
+    /// Block time in milliseconds.
+    pub date: u64,
+    pub exam_type: String,
+}
+
+impl CLTyped for Exam {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Exam {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.subject.to_bytes()?);
+        result.append(&mut self.class.to_bytes()?);
+        result.append(&mut self.year.to_bytes()?);
+        result.append(&mut self.date.to_bytes()?);
+        result.append(&mut self.exam_type.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.subject.serialized_length()
+            + self.class.serialized_length()
+            + self.year.serialized_length()
+            + self.date.serialized_length()
+            + self.exam_type.serialized_length()
+    }
+}
+
+impl FromBytes for Exam {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (subject, rem) = String::from_bytes(bytes)?;
+        let (class, rem) = String::from_bytes(rem)?;
+        let (year, rem) = u32::from_bytes(rem)?;
+        let (date, rem) = u64::from_bytes(rem)?;
+        let (exam_type, rem) = String::from_bytes(rem)?;
+        Ok((Exam { subject, class, year, date, exam_type }, rem))
+    }
+}
+
+/// Running aggregate of an exam's grades, with one histogram bucket per step of the grading scale.
+/// It holds no reference to the students, so it can be shown to anyone.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExamStats {
+    pub sum: u64,
+    pub histogram: Vec<u32>,
+}
+
+impl ExamStats {
+    pub fn new(scale: &GradingScale) -> Self {
+        ExamStats { sum: 0, histogram: vec![0; scale.steps()] }
+    }
+
+    pub fn add(&mut self, scale: &GradingScale, grade: u32) {
+        self.sum += grade as u64;
+        self.histogram[scale.bucket(grade)] += 1;
+    }
+
+    pub fn remove(&mut self, scale: &GradingScale, grade: u32) {
+        self.sum = self.sum.saturating_sub(grade as u64);
+        let bucket = &mut self.histogram[scale.bucket(grade)];
+        *bucket = bucket.saturating_sub(1);
+    }
+
+    pub fn count(&self) -> u32 {
+        self.histogram.iter().sum()
+    }
+
+    /// Count, mean, median, lowest and highest grade; the mean and median are rounded like averages.
+    pub fn summary(&self, scale: &GradingScale) -> ExamSummary {
+        let count = self.count();
+        if count == 0 {
+            return ExamSummary { histogram: self.histogram.clone(), ..ExamSummary::default() };
+        }
+        let nth = |n: u32| {
+            let mut seen = 0;
+            for (bucket, grades) in self.histogram.iter().enumerate() {
+                seen += grades;
+                if seen > n {
+                    return scale.grade(bucket);
+                }
+            }
+            scale.max
+        };
+        ExamSummary {
+            count,
+            mean: average((self.sum, count)),
+            median: average((nth((count - 1) / 2) as u64 + nth(count / 2) as u64, 2)),
+            min: Some(nth(0)),
+            max: Some(nth(count - 1)),
+            histogram: self.histogram.clone(),
+        }
+    }
+}
+
+impl CLTyped for ExamStats {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for ExamStats {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.sum.to_bytes()?);
+        result.append(&mut self.histogram.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.sum.serialized_length() + self.histogram.serialized_length()
+    }
+}
+
+impl FromBytes for ExamStats {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (sum, rem) = u64::from_bytes(bytes)?;
+        let (histogram, rem) = Vec::<u32>::from_bytes(rem)?;
+        Ok((ExamStats { sum, histogram }, rem))
+    }
+}
+
+/// What the principal gets to see of an exam. The statistics are `None` until a grade is given.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExamSummary {
+    pub count: u32,
+    pub mean: Option<u32>,
+    pub median: Option<u32>,
+    pub min: Option<u32>,
+    pub max: Option<u32>,
+    pub histogram: Vec<u32>,
+}
+
+impl CLTyped for ExamSummary {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for ExamSummary {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.count.to_bytes()?);
+        result.append(&mut self.mean.to_bytes()?);
+        result.append(&mut self.median.to_bytes()?);
+        result.append(&mut self.min.to_bytes()?);
+        result.append(&mut self.max.to_bytes()?);
+        result.append(&mut self.histogram.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.count.serialized_length()
+            + self.mean.serialized_length()
+            + self.median.serialized_length()
+            + self.min.serialized_length()
+            + self.max.serialized_length()
+            + self.histogram.serialized_length()
+    }
+}
+
+impl FromBytes for ExamSummary {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (count, rem) = u32::from_bytes(bytes)?;
+        let (mean, rem) = Option::<u32>::from_bytes(rem)?;
+        let (median, rem) = Option::<u32>::from_bytes(rem)?;
+        let (min, rem) = Option::<u32>::from_bytes(rem)?;
+        let (max, rem) = Option::<u32>::from_bytes(rem)?;
+        let (histogram, rem) = Vec::<u32>::from_bytes(rem)?;
+        Ok((ExamSummary { count, mean, median, min, max, histogram }, rem))
+    }
+}
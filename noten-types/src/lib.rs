@@ -0,0 +1,18 @@
+//! The types the noten contract stores and the errors it reverts with. The contract and
+//! `noten-client` both depend on this crate, so they always serialize them the same way.
+
+#![no_std]
+
+extern crate alloc;
+
+pub mod appeal;
+pub mod appointment;
+pub mod error;
+pub mod exam;
+pub mod origin;
+pub mod page;
+pub mod proposal;
+pub mod record;
+pub mod scale;
+pub mod total;
+pub mod transfer;
@@ -0,0 +1,113 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped, Key, U256};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+use cep47::TokenId;
+
+/// Where a grade that wasn't issued by this school comes from. Such grades can't be changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GradeOrigin {
+    /// Imported from the transfer record `transfer_id` of another school's contract package.
+    Transfer {
+        school: Key,
+        transfer_id: u32,
+        token_id: TokenId,
+    },
+    /// Earned at another institution, e.g. on an exchange, and converted to the local scale.
+    /// Unlike transferred grades, these count towards the student's averages here.
+    Recognition(Recognition),
+}
+
+/// How an external grade was converted: `value` on the institution's `scale` became the local grade by `rule`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Recognition {
+    pub institution: String,
+    pub scale: String,
+    pub value: String,
+    pub rule: String,
+}
+
+const TRANSFER_TAG: u8 = 0;
+const RECOGNITION_TAG: u8 = 1;
+
+impl CLTyped for GradeOrigin {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for GradeOrigin {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        match self {
+            GradeOrigin::Transfer { school, transfer_id, token_id } => {
+                result.push(TRANSFER_TAG);
+                result.append(&mut school.to_bytes()?);
+                result.append(&mut transfer_id.to_bytes()?);
+                result.append(&mut token_id.to_bytes()?);
+            }
+            GradeOrigin::Recognition(recognition) => {
+                result.push(RECOGNITION_TAG);
+                result.append(&mut recognition.to_bytes()?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                GradeOrigin::Transfer { school, transfer_id, token_id } => {
+                    school.serialized_length() + transfer_id.serialized_length() + token_id.serialized_length()
+                }
+                GradeOrigin::Recognition(recognition) => recognition.serialized_length(),
+            }
+    }
+}
+
+impl FromBytes for GradeOrigin {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        match tag {
+            TRANSFER_TAG => {
+                let (school, rem) = Key::from_bytes(rem)?;
+                let (transfer_id, rem) = u32::from_bytes(rem)?;
+                let (token_id, rem) = U256::from_bytes(rem)?;
+                Ok((GradeOrigin::Transfer { school, transfer_id, token_id }, rem))
+            }
+            RECOGNITION_TAG => {
+                let (recognition, rem) = Recognition::from_bytes(rem)?;
+                Ok((GradeOrigin::Recognition(recognition), rem))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+impl ToBytes for Recognition {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.institution.to_bytes()?);
+        result.append(&mut self.scale.to_bytes()?);
+        result.append(&mut self.value.to_bytes()?);
+        result.append(&mut self.rule.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.institution.serialized_length()
+            + self.scale.serialized_length()
+            + self.value.serialized_length()
+            + self.rule.serialized_length()
+    }
+}
+
+impl FromBytes for Recognition {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (institution, rem) = String::from_bytes(bytes)?;
+        let (scale, rem) = String::from_bytes(rem)?;
+        let (value, rem) = String::from_bytes(rem)?;
+        let (rule, rem) = String::from_bytes(rem)?;
+        Ok((Recognition { institution, scale, value, rule }, rem))
+    }
+}
@@ -0,0 +1,10 @@
+use core::ops::Range;
+
+/// Most grades or events a single page query returns, so paging stays within deploy gas limits.
+pub const MAX_PAGE_SIZE: u64 = 100;
+
+/// Positions of the page of at most `limit`, and never more than `MAX_PAGE_SIZE`, entries from
+/// `offset` on in a list of `count`; empty once `offset` is past the end.
+pub fn page(offset: u64, limit: u64, count: u64) -> Range<u64> {
+    offset..count.min(offset.saturating_add(limit.min(MAX_PAGE_SIZE)))
+}
@@ -0,0 +1,157 @@
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped, Key, U256};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH};
+use cep47::TokenId;
+
+use crate::appointment::Appointment;
+
+/// A sensitive administrative action that needs the approval of several administrators.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    RemoveTeacher(Key),
+    RevokeGrade(TokenId),
+    SetApprovalThreshold(u32),
+    Unpause,
+    ReopenTerm(u32),
+    AddTeacher(Key),
+    AppointTeacher(Key, Appointment),
+}
+
+const REMOVE_TEACHER_TAG: u8 = 0;
+const REVOKE_GRADE_TAG: u8 = 1;
+const SET_APPROVAL_THRESHOLD_TAG: u8 = 2;
+const UNPAUSE_TAG: u8 = 3;
+const REOPEN_TERM_TAG: u8 = 4;
+const ADD_TEACHER_TAG: u8 = 5;
+const APPOINT_TEACHER_TAG: u8 = 6;
+
+impl ToBytes for Action {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        match self {
+            Action::RemoveTeacher(teacher) => {
+                result.push(REMOVE_TEACHER_TAG);
+                result.append(&mut teacher.to_bytes()?);
+            }
+            Action::RevokeGrade(token_id) => {
+                result.push(REVOKE_GRADE_TAG);
+                result.append(&mut token_id.to_bytes()?);
+            }
+            Action::SetApprovalThreshold(threshold) => {
+                result.push(SET_APPROVAL_THRESHOLD_TAG);
+                result.append(&mut threshold.to_bytes()?);
+            }
+            Action::Unpause => result.push(UNPAUSE_TAG),
+            Action::ReopenTerm(year) => {
+                result.push(REOPEN_TERM_TAG);
+                result.append(&mut year.to_bytes()?);
+            }
+            Action::AddTeacher(teacher) => {
+                result.push(ADD_TEACHER_TAG);
+                result.append(&mut teacher.to_bytes()?);
+            }
+            Action::AppointTeacher(teacher, appointment) => {
+                result.push(APPOINT_TEACHER_TAG);
+                result.append(&mut teacher.to_bytes()?);
+                result.append(&mut appointment.to_bytes()?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        U8_SERIALIZED_LENGTH
+            + match self {
+                Action::RemoveTeacher(teacher) => teacher.serialized_length(),
+                Action::RevokeGrade(token_id) => token_id.serialized_length(),
+                Action::SetApprovalThreshold(threshold) => threshold.serialized_length(),
+                Action::Unpause => 0,
+                Action::ReopenTerm(year) => year.serialized_length(),
+                Action::AddTeacher(teacher) => teacher.serialized_length(),
+                Action::AppointTeacher(teacher, appointment) => teacher.serialized_length() + appointment.serialized_length(),
+            }
+    }
+}
+
+impl FromBytes for Action {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (tag, rem) = u8::from_bytes(bytes)?;
+        match tag {
+            REMOVE_TEACHER_TAG => {
+                let (teacher, rem) = Key::from_bytes(rem)?;
+                Ok((Action::RemoveTeacher(teacher), rem))
+            }
+            REVOKE_GRADE_TAG => {
+                let (token_id, rem) = U256::from_bytes(rem)?;
+                Ok((Action::RevokeGrade(token_id), rem))
+            }
+            SET_APPROVAL_THRESHOLD_TAG => {
+                let (threshold, rem) = u32::from_bytes(rem)?;
+                Ok((Action::SetApprovalThreshold(threshold), rem))
+            }
+            UNPAUSE_TAG => Ok((Action::Unpause, rem)),
+            REOPEN_TERM_TAG => {
+                let (year, rem) = u32::from_bytes(rem)?;
+                Ok((Action::ReopenTerm(year), rem))
+            }
+            ADD_TEACHER_TAG => {
+                let (teacher, rem) = Key::from_bytes(rem)?;
+                Ok((Action::AddTeacher(teacher), rem))
+            }
+            APPOINT_TEACHER_TAG => {
+                let (teacher, rem) = Key::from_bytes(rem)?;
+                let (appointment, rem) = Appointment::from_bytes(rem)?;
+                Ok((Action::AppointTeacher(teacher, appointment), rem))
+            }
+            _ => Err(bytesrepr::Error::Formatting),
+        }
+    }
+}
+
+/// An action waiting for approvals. It executes as soon as the approval threshold is reached
+/// and can no longer be approved once `deadline` (block time in milliseconds) has passed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Proposal {
+    pub action: Action,
+    pub proposer: Key,
+    pub deadline: u64,
+    pub approvals: Vec<Key>,
+    pub executed: bool,
+}
+
+impl CLTyped for Proposal {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Proposal {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.action.to_bytes()?);
+        result.append(&mut self.proposer.to_bytes()?);
+        result.append(&mut self.deadline.to_bytes()?);
+        result.append(&mut self.approvals.to_bytes()?);
+        result.append(&mut self.executed.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.action.serialized_length()
+            + self.proposer.serialized_length()
+            + self.deadline.serialized_length()
+            + self.approvals.serialized_length()
+            + self.executed.serialized_length()
+    }
+}
+
+impl FromBytes for Proposal {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (action, rem) = Action::from_bytes(bytes)?;
+        let (proposer, rem) = Key::from_bytes(rem)?;
+        let (deadline, rem) = u64::from_bytes(rem)?;
+        let (approvals, rem) = Vec::<Key>::from_bytes(rem)?;
+        let (executed, rem) = bool::from_bytes(rem)?;
+        Ok((Proposal { action, proposer, deadline, approvals, executed }, rem))
+    }
+}
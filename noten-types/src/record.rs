@@ -0,0 +1,75 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped, Key};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+/// Typed counterpart of a grade's `Meta`, including who issued it, when, and with which contract version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GradeRecord {
+    pub student: Key,
+    pub subject: String,
+    pub year: u32,
+    pub grade_type: String,
+    pub grade: u32,
+    pub teacher: Key,
+    /// Block time in milliseconds.
+    pub issued_at: u64,
+    /// The package's `ContractVersion` that issued the grade, 0 for grades from before records existed.
+    pub contract_version: u32,
+}
+
+impl CLTyped for GradeRecord {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for GradeRecord {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.student.to_bytes()?);
+        result.append(&mut self.subject.to_bytes()?);
+        result.append(&mut self.year.to_bytes()?);
+        result.append(&mut self.grade_type.to_bytes()?);
+        result.append(&mut self.grade.to_bytes()?);
+        result.append(&mut self.teacher.to_bytes()?);
+        result.append(&mut self.issued_at.to_bytes()?);
+        result.append(&mut self.contract_version.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.student.serialized_length()
+            + self.subject.serialized_length()
+            + self.year.serialized_length()
+            + self.grade_type.serialized_length()
+            + self.grade.serialized_length()
+            + self.teacher.serialized_length()
+            + self.issued_at.serialized_length()
+            + self.contract_version.serialized_length()
+    }
+}
+
+impl FromBytes for GradeRecord {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (student, rem) = Key::from_bytes(bytes)?;
+        let (subject, rem) = String::from_bytes(rem)?;
+        let (year, rem) = u32::from_bytes(rem)?;
+        let (grade_type, rem) = String::from_bytes(rem)?;
+        let (grade, rem) = u32::from_bytes(rem)?;
+        let (teacher, rem) = Key::from_bytes(rem)?;
+        let (issued_at, rem) = u64::from_bytes(rem)?;
+        let (contract_version, rem) = u32::from_bytes(rem)?;
+        let record = GradeRecord {
+            student,
+            subject,
+            year,
+            grade_type,
+            grade,
+            teacher,
+            issued_at,
+            contract_version,
+        };
+        Ok((record, rem))
+    }
+}
@@ -0,0 +1,74 @@
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+
+/// Most grades a scale can have, so an exam's histogram, which has a bucket per grade, stays small
+/// enough to be stored and updated with every grade.
+pub const MAX_STEPS: u32 = 100;
+
+/// The grades teachers can give: `min` to `max` in steps of `step`, e.g. 1.0 to 6.0 in half grades
+/// is `GradingScale { min: 10, max: 60, step: 5 }`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GradingScale {
+    pub min: u32,
+    pub max: u32,
+    pub step: u32,
+}
+
+impl Default for GradingScale {
+    fn default() -> Self {
+        GradingScale { min: 10, max: 60, step: 5 }
+    }
+}
+
+impl GradingScale {
+    pub fn is_valid(&self) -> bool {
+        self.step > 0 && self.min < self.max && (self.max - self.min) % self.step == 0 && (self.max - self.min) / self.step < MAX_STEPS
+    }
+
+    pub fn contains(&self, grade: u32) -> bool {
+        self.min <= grade && grade <= self.max && (grade - self.min) % self.step == 0
+    }
+
+    /// Number of grades on the scale, which is also the number of histogram buckets.
+    pub fn steps(&self) -> usize {
+        ((self.max - self.min) / self.step) as usize + 1
+    }
+
+    pub fn bucket(&self, grade: u32) -> usize {
+        ((grade - self.min) / self.step) as usize
+    }
+
+    pub fn grade(&self, bucket: usize) -> u32 {
+        self.min + bucket as u32 * self.step
+    }
+}
+
+impl CLTyped for GradingScale {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for GradingScale {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.min.to_bytes()?);
+        result.append(&mut self.max.to_bytes()?);
+        result.append(&mut self.step.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.min.serialized_length() + self.max.serialized_length() + self.step.serialized_length()
+    }
+}
+
+impl FromBytes for GradingScale {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (min, rem) = u32::from_bytes(bytes)?;
+        let (max, rem) = u32::from_bytes(rem)?;
+        let (step, rem) = u32::from_bytes(rem)?;
+        Ok((GradingScale { min, max, step }, rem))
+    }
+}
@@ -0,0 +1,11 @@
+/// Sum and count of a student's grades in one subject and school year.
+pub type GradeTotal = (u64, u32);
+
+/// The average of a total, rounded half up to the grade scale's unit, or `None` without grades.
+/// Everything that shows an average rounds it with this, so the contract and its readers agree.
+pub fn average((sum, count): GradeTotal) -> Option<u32> {
+    if count == 0 {
+        return None;
+    }
+    Some(((sum * 2 + count as u64) / (count as u64 * 2)) as u32)
+}
@@ -0,0 +1,57 @@
+use alloc::vec::Vec;
+use casper_types::{CLType, CLTyped, Key, U256};
+use casper_types::bytesrepr::{self, FromBytes, ToBytes};
+use cep47::TokenId;
+use crate::record::GradeRecord;
+
+/// Grades a school hands over to another school when a student moves there.
+///
+/// The record lives in the sending school's contract, so the receiving school can trust it by
+/// reading it from there rather than from whoever submits it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Transfer {
+    pub student: Key,
+    /// Contract package of the receiving school.
+    pub destination: Key,
+    pub grades: Vec<(TokenId, GradeRecord)>,
+    pub issued_by: Key,
+    /// Block time in milliseconds.
+    pub issued_at: u64,
+}
+
+impl CLTyped for Transfer {
+    fn cl_type() -> CLType {
+        CLType::Any
+    }
+}
+
+impl ToBytes for Transfer {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        result.append(&mut self.student.to_bytes()?);
+        result.append(&mut self.destination.to_bytes()?);
+        result.append(&mut self.grades.to_bytes()?);
+        result.append(&mut self.issued_by.to_bytes()?);
+        result.append(&mut self.issued_at.to_bytes()?);
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.student.serialized_length()
+            + self.destination.serialized_length()
+            + self.grades.serialized_length()
+            + self.issued_by.serialized_length()
+            + self.issued_at.serialized_length()
+    }
+}
+
+impl FromBytes for Transfer {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (student, rem) = Key::from_bytes(bytes)?;
+        let (destination, rem) = Key::from_bytes(rem)?;
+        let (grades, rem) = Vec::<(U256, GradeRecord)>::from_bytes(rem)?;
+        let (issued_by, rem) = Key::from_bytes(rem)?;
+        let (issued_at, rem) = u64::from_bytes(rem)?;
+        Ok((Transfer { student, destination, grades, issued_by, issued_at }, rem))
+    }
+}
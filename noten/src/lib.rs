@@ -0,0 +1,2466 @@
+//! The noten contract's code: its storage, rules and entry points. `noten.wasm` installs it for
+//! the deploying account, and the canton registry, which is built with it, installs it for new
+//! schools with `install_school`.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+use alloc::{format, vec};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::sync::atomic::AtomicU32;
+use casper_contract::contract_api::{runtime, storage};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLType, CLTyped, CLValue, ContractHash, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPoints, EntryPointType, Group, Key, Parameter, PublicKey, runtime_args, RuntimeArgs, U256, URef};
+use casper_types::bytesrepr::{Bytes, ToBytes};
+use casper_types::contracts::{ContractVersion, NamedKeys};
+use cep47::{CEP47, Error, Meta, TokenId};
+use cep47::contract_utils::{AdminControl, ContractContext, Dict, get_key, key_and_value_to_str, key_to_str, OnChainContractStorage, set_key};
+use noten_types::appeal::{Appeal, AppealStatus};
+use noten_types::appointment::Appointment;
+use noten_types::error::NotenError;
+use noten_types::exam::{Exam, ExamStats, ExamSummary};
+use noten_types::origin::{GradeOrigin, Recognition};
+use noten_types::page::page;
+use noten_types::proposal::{Action, Proposal};
+use noten_types::record::GradeRecord;
+use noten_types::scale::GradingScale;
+use noten_types::total::{self, GradeTotal};
+use noten_types::transfer::Transfer;
+
+/// Named key of the package's `ContractVersion` this code was installed as, recorded on every
+/// grade it issues. Whoever installed the package keeps the uref too, to update it on upgrades.
+pub const CONTRACT_VERSION_KEY: &str = "contract_version";
+/// Version of the storage layout; 1 is the original release, which kept grades in `Meta` only,
+/// 2 added grade records, 3 the grade index and 4 the staff log.
+const SCHEMA_VERSION: u32 = 4;
+
+const GRADE_PAYLOADS_DICT: &str = "grade_payloads";
+const PSEUDONYM_COMMITMENTS_DICT: &str = "pseudonym_commitments";
+/// Claimed pseudonyms and their students' accounts, readable by anyone, see `claim_pseudonym`.
+const PSEUDONYM_ACCOUNTS_DICT: &str = "pseudonym_accounts";
+const GUARDIANS_DICT: &str = "guardians";
+const ACKNOWLEDGEMENTS_DICT: &str = "grade_acknowledgements";
+const APPEALS_DICT: &str = "appeals";
+const REVIEW_BOARD_DICT: &str = "review_board";
+const REVOKED_GRADES_DICT: &str = "revoked_grades";
+const PROPOSALS_DICT: &str = "proposals";
+const PROPOSAL_COUNT_KEY: &str = "proposal_count";
+const APPROVAL_THRESHOLD_KEY: &str = "approval_threshold";
+const PERMANENT_TEACHERS_DICT: &str = "permanent_teachers";
+const PERMANENT_TEACHER_COUNT_KEY: &str = "permanent_teacher_count";
+const APPOINTMENTS_DICT: &str = "appointments";
+const STUDENT_CLASSES_DICT: &str = "student_classes";
+const GRADE_RECORDS_DICT: &str = "grade_records";
+const SCHEMA_VERSION_KEY: &str = "schema_version";
+const PAUSED_KEY: &str = "paused";
+const STUDENTS_DICT: &str = "students";
+const REGISTERED_STUDENTS_DICT: &str = "registered_students";
+const STUDENT_COUNT_KEY: &str = "student_count";
+const GRADE_TOTALS_DICT: &str = "grade_totals";
+const STUDENT_SUBJECTS_DICT: &str = "student_subjects";
+const FINAL_AVERAGES_DICT: &str = "final_averages";
+const CLOSED_TERMS_DICT: &str = "closed_terms";
+const GRADUATES_DICT: &str = "graduates";
+const YEAR_ARCHIVES_DICT: &str = "year_archives";
+const FINAL_CLASS_LEVEL_KEY: &str = "final_class_level";
+const ROLLOVER_YEAR_KEY: &str = "rollover_year";
+const ROLLOVER_CURSOR_KEY: &str = "rollover_cursor";
+const TRANSFERS_DICT: &str = "transfers";
+const TRANSFER_COUNT_KEY: &str = "transfer_count";
+const IMPORTED_TRANSFERS_DICT: &str = "imported_transfers";
+const REGISTRY_KEY: &str = "registry";
+const GRADE_ORIGINS_DICT: &str = "grade_origins";
+const GRADES_DICT: &str = "grades";
+const GRADE_POSITIONS_DICT: &str = "grade_positions";
+const GRADE_COUNT_KEY: &str = "grade_count";
+const LEGACY_GRADE_COUNT_KEY: &str = "legacy_grade_count";
+const EVENTS_DICT: &str = "events";
+const EVENT_COUNT_KEY: &str = "event_count";
+const STAFF_EVENTS_DICT: &str = "staff_events";
+const STAFF_EVENT_COUNT_KEY: &str = "staff_event_count";
+const GRADING_SCALE_KEY: &str = "grading_scale";
+const EXAMS_DICT: &str = "exams";
+const EXAM_COUNT_KEY: &str = "exam_count";
+const EXAM_STATS_DICT: &str = "exam_stats";
+const GRADE_EXAMS_DICT: &str = "grade_exams";
+
+/// Reference to the archived snapshot of a school year, when it was closed and how many students it covered.
+type YearArchive = (String, u64, u64);
+
+/// Encrypted grade details: the recipients' public keys and the ciphertext envelope for them.
+type GradePayload = (Vec<PublicKey>, Bytes);
+
+#[derive(Default)]
+struct NotenContract(OnChainContractStorage);
+
+
+impl ContractContext<OnChainContractStorage> for NotenContract {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl CEP47<OnChainContractStorage> for NotenContract {}
+impl AdminControl<OnChainContractStorage> for NotenContract {}
+
+impl NotenContract {
+    fn constructor(&mut self, name: String, symbol: String, meta: Meta) {
+        CEP47::init(self, name, symbol, meta);
+        AdminControl::init(self);
+        self.init_storage();
+        set_key(SCHEMA_VERSION_KEY, SCHEMA_VERSION);
+    }
+    /// Creates the storage added since the first release, leaving what already exists untouched.
+    fn init_storage(&mut self) {
+        let dicts = [
+            GRADE_PAYLOADS_DICT,
+            PSEUDONYM_COMMITMENTS_DICT,
+            PSEUDONYM_ACCOUNTS_DICT,
+            GUARDIANS_DICT,
+            ACKNOWLEDGEMENTS_DICT,
+            APPEALS_DICT,
+            REVIEW_BOARD_DICT,
+            REVOKED_GRADES_DICT,
+            PROPOSALS_DICT,
+            PERMANENT_TEACHERS_DICT,
+            APPOINTMENTS_DICT,
+            STUDENT_CLASSES_DICT,
+            GRADE_RECORDS_DICT,
+            STUDENTS_DICT,
+            REGISTERED_STUDENTS_DICT,
+            GRADE_TOTALS_DICT,
+            STUDENT_SUBJECTS_DICT,
+            FINAL_AVERAGES_DICT,
+            CLOSED_TERMS_DICT,
+            GRADUATES_DICT,
+            YEAR_ARCHIVES_DICT,
+            TRANSFERS_DICT,
+            IMPORTED_TRANSFERS_DICT,
+            GRADE_ORIGINS_DICT,
+            GRADES_DICT,
+            GRADE_POSITIONS_DICT,
+            EVENTS_DICT,
+            STAFF_EVENTS_DICT,
+            EXAMS_DICT,
+            EXAM_STATS_DICT,
+            GRADE_EXAMS_DICT,
+        ];
+        for name in dicts.iter() {
+            if !runtime::has_key(name) {
+                Dict::init(name);
+            }
+        }
+        if !runtime::has_key(PROPOSAL_COUNT_KEY) {
+            set_key(PROPOSAL_COUNT_KEY, 0u32);
+        }
+        if !runtime::has_key(APPROVAL_THRESHOLD_KEY) {
+            set_key(APPROVAL_THRESHOLD_KEY, 1u32);
+        }
+        if !runtime::has_key(PERMANENT_TEACHER_COUNT_KEY) {
+            set_key(PERMANENT_TEACHER_COUNT_KEY, 0u32);
+        }
+        if !runtime::has_key(PAUSED_KEY) {
+            set_key(PAUSED_KEY, false);
+        }
+        if !runtime::has_key(STUDENT_COUNT_KEY) {
+            set_key(STUDENT_COUNT_KEY, 0u64);
+        }
+        if !runtime::has_key(TRANSFER_COUNT_KEY) {
+            set_key(TRANSFER_COUNT_KEY, 0u32);
+        }
+        if !runtime::has_key(GRADE_COUNT_KEY) {
+            set_key(GRADE_COUNT_KEY, 0u64);
+        }
+        if !runtime::has_key(LEGACY_GRADE_COUNT_KEY) {
+            set_key(LEGACY_GRADE_COUNT_KEY, 0u64);
+        }
+        if !runtime::has_key(EVENT_COUNT_KEY) {
+            set_key(EVENT_COUNT_KEY, 0u64);
+        }
+        if !runtime::has_key(STAFF_EVENT_COUNT_KEY) {
+            set_key(STAFF_EVENT_COUNT_KEY, 0u64);
+        }
+        if !runtime::has_key(EXAM_COUNT_KEY) {
+            set_key(EXAM_COUNT_KEY, 0u32);
+        }
+        if !runtime::has_key(REGISTRY_KEY) {
+            set_key(REGISTRY_KEY, Option::<Key>::None);
+        }
+        if !runtime::has_key(GRADING_SCALE_KEY) {
+            set_key(GRADING_SCALE_KEY, GradingScale::default());
+        }
+        if !runtime::has_key(FINAL_CLASS_LEVEL_KEY) {
+            //Gymnasium classes run from the first to the fourth year
+            set_key(FINAL_CLASS_LEVEL_KEY, 4u32);
+        }
+    }
+    /// Brings storage of an upgraded package up to date, adds the given grades to the grade index
+    /// and gives them a `GradeRecord`. Grades from before records existed have no known teacher or
+    /// issue time; they're recorded as issued by the contract itself at time 0 with contract version 0.
+    /// Runs while paused too, so a school can pause, upgrade and migrate before grading resumes.
+    /// Packages from before teachers were counted only know their migrating teacher as permanent;
+    /// the others are counted once they're added again.
+    fn migrate(&mut self, token_ids: Vec<TokenId>) {
+        self.assert_teacher();
+        self.init_storage();
+        let caller = self.get_caller();
+        if self.appointment(caller).is_none() {
+            self.set_permanent(caller, true);
+        }
+        for token_id in token_ids {
+            if self.grade_position(token_id).is_none() {
+                self.index_grade(token_id);
+                self.record_event("migrated", token_id);
+            }
+            if self.grade_record(token_id).is_some() {
+                continue;
+            }
+            let student = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+            let mut meta = self.token_meta(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+            let field = |name: &str| meta.get(name).cloned().unwrap_or_revert_with(NotenError::MalformedGrade);
+            let record = GradeRecord {
+                student,
+                subject: field("subject"),
+                year: field("year").parse().unwrap_or_revert_with(NotenError::MalformedGrade),
+                grade_type: field("grade_type"),
+                grade: field("grade").parse().unwrap_or_revert_with(NotenError::MalformedGrade),
+                teacher: self.self_addr(),
+                issued_at: 0,
+                contract_version: 0,
+            };
+            meta.insert("teacher".to_string(), record.teacher.to_formatted_string());
+            meta.insert("issued_at".to_string(), record.issued_at.to_string());
+            meta.insert("contract_version".to_string(), record.contract_version.to_string());
+            self.register_student(student);
+            if !self.is_revoked(token_id) {
+                self.add_to_total(student, &record.subject, record.year, record.grade);
+            }
+            Dict::instance(GRADE_RECORDS_DICT).set(&token_id.to_string(), record);
+            self.set_token_meta(token_id, meta).unwrap_or_revert();
+        }
+        set_key(SCHEMA_VERSION_KEY, SCHEMA_VERSION);
+    }
+    /// Mints a grade read from the package `source_package`, which can't be upgraded, in the format
+    /// that version stored: only its metadata, without a `GradeRecord`. `migrate` then converts it
+    /// like the grades of an upgraded package. Only possible while every token of this package is
+    /// such an import, i.e. before the first grade is given, so it can't be used to issue grades
+    /// that bypass the checks of `grade`.
+    fn import_legacy_grade(&mut self, student: Key, subject: String, year: u32, grade_type: String, grade: u32, source_package: Key) -> Result<Vec<TokenId>, Error> {
+        self.assert_permanent_teacher();
+        self.assert_not_paused();
+        let legacy_count: u64 = get_key(LEGACY_GRADE_COUNT_KEY).unwrap_or_default();
+        if self.total_supply() != U256::from(legacy_count) {
+            runtime::revert(NotenError::LegacyImportClosed);
+        }
+        set_key(LEGACY_GRADE_COUNT_KEY, legacy_count + 1);
+        let mut meta = Meta::new();
+        meta.insert("subject".to_string(), subject);
+        meta.insert("year".to_string(), year.to_string());
+        meta.insert("grade_type".to_string(), grade_type);
+        meta.insert("grade".to_string(), grade.to_string());
+        meta.insert("imported_by".to_string(), self.get_caller().to_formatted_string());
+        meta.insert("source_package".to_string(), source_package.to_formatted_string());
+        self.mint(student, Option::None, vec![meta])
+    }
+    fn contract_version(&self) -> ContractVersion {
+        get_key(CONTRACT_VERSION_KEY).unwrap_or_default()
+    }
+    fn grade(&mut self, student: Key, subject: String, year: u32, grade_type: String, grade: u32) -> Result<Vec<TokenId>, Error> {
+        self.issue_grade(student, subject, year, grade_type, grade, None)
+    }
+    /// Grades a student of the exam's class; the grade's subject, year and type are the exam's.
+    fn grade_exam(&mut self, student: Key, exam_id: u32, grade: u32) -> Result<Vec<TokenId>, Error> {
+        let exam = self.exam(exam_id).unwrap_or_revert_with(NotenError::UnknownExam);
+        if self.student_class(self.student_account(student)).as_ref() != Some(&exam.class) {
+            runtime::revert(NotenError::WrongClass);
+        }
+        self.issue_grade(student, exam.subject, exam.year, exam.exam_type, grade, Some(exam_id))
+    }
+    fn issue_grade(&mut self, student: Key, subject: String, year: u32, grade_type: String, grade: u32, exam_id: Option<u32>) -> Result<Vec<TokenId>, Error> {
+        //Check if the person who is calling this code is a teacher for this subject and student
+        self.assert_teacher_for(&subject, student);
+        self.assert_not_paused();
+        self.assert_term_open(year);
+        self.assert_on_scale(grade);
+        let teacher = self.get_caller();
+        let issued_at: u64 = runtime::get_blocktime().into();
+        let mut meta = Meta::new();
+        meta.insert("subject".to_string(), subject.clone());
+        meta.insert("year" .to_string(), year.to_string());
+        meta.insert("grade_type".to_string(), grade_type.clone());
+        meta.insert("grade".to_string(), grade.to_string());
+        meta.insert("teacher".to_string(), teacher.to_formatted_string());
+        meta.insert("issued_at".to_string(), issued_at.to_string());
+        meta.insert("contract_version".to_string(), self.contract_version().to_string());
+        if let Some(exam_id) = exam_id {
+            meta.insert("exam_id".to_string(), exam_id.to_string());
+        }
+        let mut list_of_meta = Vec::<Meta>::new();
+        list_of_meta.push(meta);
+
+        let token_ids = self.mint(student, Option::None, list_of_meta)?;
+        self.register_student(student);
+        self.add_to_total(student, &subject, year, grade);
+        let record = GradeRecord {
+            student,
+            subject,
+            year,
+            grade_type,
+            grade,
+            teacher,
+            issued_at,
+            contract_version: self.contract_version(),
+        };
+        for token_id in &token_ids {
+            Dict::instance(GRADE_RECORDS_DICT).set(&token_id.to_string(), record.clone());
+            if let Some(exam_id) = exam_id {
+                Dict::instance(GRADE_EXAMS_DICT).set(&token_id.to_string(), exam_id);
+                self.add_to_exam(*token_id, grade);
+            }
+            self.index_grade(*token_id);
+            self.record_event("issued", *token_id);
+        }
+        Ok(token_ids)
+    }
+    fn grade_record(&self, token_id: TokenId) -> Option<GradeRecord> {
+        Dict::instance(GRADE_RECORDS_DICT).get(&token_id.to_string())
+    }
+    fn update_grade(&mut self, student: Key, grade: u32, token_id: TokenId) -> Result<(), Error> {
+        let subject = self.token_meta(token_id).and_then(|meta| meta.get("subject").cloned()).unwrap_or_default();
+        self.assert_teacher_for(&subject, student);
+        self.set_grade(student, grade, token_id)
+    }
+    fn set_grade(&mut self, student: Key, grade: u32, token_id: TokenId) -> Result<(), Error> {
+        self.assert_not_paused();
+        self.assert_issued_here(token_id);
+        self.assert_on_scale(grade);
+        let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        if owner != student {
+            runtime::revert(NotenError::StudentMismatch);
+        }
+        if self.is_revoked(token_id) {
+            runtime::revert(NotenError::GradeRevoked);
+        }
+        if let Some(mut record) = self.grade_record(token_id) {
+            self.assert_term_open(record.year);
+            self.remove_from_total(owner, &record.subject, record.year, record.grade);
+            self.add_to_total(owner, &record.subject, record.year, grade);
+            self.remove_from_exam(token_id, record.grade);
+            self.add_to_exam(token_id, grade);
+            record.grade = grade;
+            Dict::instance(GRADE_RECORDS_DICT).set(&token_id.to_string(), record);
+        }
+        let mut meta = self.token_meta(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        meta.insert("grade".to_string(), grade.to_string());
+        self.record_event("updated", token_id);
+        self.set_token_meta(token_id, meta)
+    }
+    fn set_grade_payload(&mut self, token_id: TokenId, recipients: Vec<PublicKey>, payload: Bytes) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        //The student has to be able to read their own grade, unless they haven't claimed their pseudonym yet
+        let mut readers = self.guardians_of(owner);
+        let student = self.student_account(owner);
+        if let Key::Account(_) = student {
+            readers.push(student);
+        }
+        for reader in readers {
+            if !recipients.iter().any(|recipient| Key::Account(recipient.to_account_hash()) == reader) {
+                runtime::revert(NotenError::MissingRecipient);
+            }
+        }
+        Dict::instance(GRADE_PAYLOADS_DICT).set(&token_id.to_string(), (recipients, payload));
+    }
+    fn grade_payload(&self, token_id: TokenId) -> Option<GradePayload> {
+        Dict::instance(GRADE_PAYLOADS_DICT).get(&token_id.to_string())
+    }
+    /// Registers `blake2b(pseudonym || account || nonce)`, so the pseudonym-to-account mapping
+    /// stays off-chain until the student claims it.
+    fn link_pseudonym(&mut self, pseudonym: Key, commitment: [u8; 32]) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        if pseudonym.into_hash().is_none() {
+            runtime::revert(NotenError::InvalidPseudonym);
+        }
+        if self.pseudonym_account(pseudonym).is_some() {
+            runtime::revert(NotenError::PseudonymClaimed);
+        }
+        Dict::instance(PSEUDONYM_COMMITMENTS_DICT).set(&key_to_str(&pseudonym), commitment);
+    }
+    /// Links the pseudonym to the caller for good, in the public `pseudonym_accounts` dictionary:
+    /// from then on anyone can tell which account a pseudonym's grades belong to. That's the price
+    /// of reading grades, payload recipients, guardians and appeals by account with a plain lookup;
+    /// proving the link on every read instead would need the nonce with each call. Students who want
+    /// their grades unlinked don't claim, and read them through the school or their guardians.
+    fn claim_pseudonym(&mut self, pseudonym: Key, nonce: String) {
+        if self.pseudonym_account(pseudonym).is_some() {
+            runtime::revert(NotenError::PseudonymClaimed);
+        }
+        let commitments = Dict::instance(PSEUDONYM_COMMITMENTS_DICT);
+        let commitment: [u8; 32] = commitments
+            .get(&key_to_str(&pseudonym))
+            .unwrap_or_revert_with(NotenError::InvalidClaim);
+        let student = self.get_caller();
+        let mut preimage = pseudonym.to_bytes().unwrap_or_revert();
+        preimage.append(&mut student.to_bytes().unwrap_or_revert());
+        preimage.extend_from_slice(nonce.as_bytes());
+        if runtime::blake2b(preimage) != commitment {
+            runtime::revert(NotenError::InvalidClaim);
+        }
+        commitments.remove::<[u8; 32]>(&key_to_str(&pseudonym));
+        Dict::instance(PSEUDONYM_ACCOUNTS_DICT).set(&key_to_str(&pseudonym), student);
+    }
+    fn pseudonym_account(&self, pseudonym: Key) -> Option<Key> {
+        Dict::instance(PSEUDONYM_ACCOUNTS_DICT).get(&key_to_str(&pseudonym))
+    }
+    /// The account behind a grade owner: the claiming account for pseudonyms, the owner itself otherwise.
+    fn student_account(&self, owner: Key) -> Key {
+        self.pseudonym_account(owner).unwrap_or(owner)
+    }
+    fn add_guardian(&mut self, student: Key, guardian: Key) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        let mut guardians = self.guardians(student);
+        if !guardians.contains(&guardian) {
+            guardians.push(guardian);
+            Dict::instance(GUARDIANS_DICT).set(&key_to_str(&student), guardians);
+        }
+    }
+    fn remove_guardian(&mut self, student: Key, guardian: Key) {
+        self.assert_teacher();
+        let mut guardians = self.guardians(student);
+        guardians.retain(|linked| *linked != guardian);
+        Dict::instance(GUARDIANS_DICT).set(&key_to_str(&student), guardians);
+    }
+    fn guardians(&self, student: Key) -> Vec<Key> {
+        Dict::instance(GUARDIANS_DICT).get(&key_to_str(&student)).unwrap_or_default()
+    }
+    /// Guardians linked to a grade owner, either directly or through the account that claimed it.
+    fn guardians_of(&self, owner: Key) -> Vec<Key> {
+        let mut guardians = self.guardians(owner);
+        let student = self.student_account(owner);
+        if student != owner {
+            for guardian in self.guardians(student) {
+                if !guardians.contains(&guardian) {
+                    guardians.push(guardian);
+                }
+            }
+        }
+        guardians
+    }
+    fn acknowledge_grade(&mut self, token_id: TokenId) {
+        let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        let guardian = self.get_caller();
+        if !self.guardians_of(owner).contains(&guardian) {
+            runtime::revert(NotenError::NotGuardian);
+        }
+        let acknowledged_at: u64 = runtime::get_blocktime().into();
+        Dict::instance(ACKNOWLEDGEMENTS_DICT).set(&key_and_value_to_str(&guardian, &token_id), acknowledged_at);
+    }
+    fn acknowledgement(&self, token_id: TokenId, guardian: Key) -> Option<u64> {
+        self.assert_teacher();
+        Dict::instance(ACKNOWLEDGEMENTS_DICT).get(&key_and_value_to_str(&guardian, &token_id))
+    }
+    fn file_appeal(&mut self, token_id: TokenId, reason: String) {
+        let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        let appellant = self.get_caller();
+        if appellant != self.student_account(owner) && !self.guardians_of(owner).contains(&appellant) {
+            runtime::revert(NotenError::NotAppellant);
+        }
+        if let Some(appeal) = self.appeal(token_id) {
+            if appeal.status.is_pending() {
+                runtime::revert(NotenError::AppealPending);
+            }
+        }
+        let appeal = Appeal { appellant, reason, status: AppealStatus::Open };
+        Dict::instance(APPEALS_DICT).set(&token_id.to_string(), appeal);
+    }
+    fn review_appeal(&mut self, token_id: TokenId) {
+        self.advance_appeal(token_id, AppealStatus::Open, AppealStatus::UnderReview);
+    }
+    /// Upholding an appeal replaces the grade through the same path as `update_grade`.
+    fn uphold_appeal(&mut self, token_id: TokenId, grade: u32) -> Result<(), Error> {
+        self.advance_appeal(token_id, AppealStatus::UnderReview, AppealStatus::Upheld);
+        self.assert_impartial(token_id);
+        let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        self.set_grade(owner, grade, token_id)
+    }
+    fn reject_appeal(&mut self, token_id: TokenId) {
+        self.advance_appeal(token_id, AppealStatus::UnderReview, AppealStatus::Rejected);
+        self.assert_impartial(token_id);
+    }
+    /// Appeals are decided by someone other than the grade's teacher. Teachers only decide appeals
+    /// against grades their appointment covers; review board members decide any.
+    fn assert_impartial(&self, token_id: TokenId) {
+        let decider = self.get_caller();
+        let record = match self.grade_record(token_id) {
+            Some(record) => record,
+            None => return,
+        };
+        if record.teacher == decider {
+            runtime::revert(NotenError::OwnGrade);
+        }
+        if !self.is_reviewer(decider) {
+            let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+            self.assert_teacher_for(&record.subject, owner);
+        }
+    }
+    fn advance_appeal(&mut self, token_id: TokenId, from: AppealStatus, to: AppealStatus) {
+        let reviewer = self.get_caller();
+        if !self.is_teacher(reviewer) && !self.is_reviewer(reviewer) {
+            runtime::revert(NotenError::NotReviewer);
+        }
+        let mut appeal = self.appeal(token_id).unwrap_or_revert_with(NotenError::InvalidAppealState);
+        if appeal.status != from {
+            runtime::revert(NotenError::InvalidAppealState);
+        }
+        appeal.status = to;
+        Dict::instance(APPEALS_DICT).set(&token_id.to_string(), appeal);
+    }
+    fn appeal(&self, token_id: TokenId) -> Option<Appeal> {
+        Dict::instance(APPEALS_DICT).get(&token_id.to_string())
+    }
+    fn add_reviewer(&mut self, reviewer: Key) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        Dict::instance(REVIEW_BOARD_DICT).set(&key_to_str(&reviewer), true);
+    }
+    fn remove_reviewer(&mut self, reviewer: Key) {
+        self.assert_teacher();
+        Dict::instance(REVIEW_BOARD_DICT).set(&key_to_str(&reviewer), false);
+    }
+    fn is_reviewer(&self, reviewer: Key) -> bool {
+        Dict::instance(REVIEW_BOARD_DICT).get(&key_to_str(&reviewer)).unwrap_or_default()
+    }
+    fn revoke_grade(&mut self, token_id: TokenId) -> Result<(), Error> {
+        self.assert_permanent_teacher();
+        self.assert_single_approval();
+        self.revoke(token_id)
+    }
+    fn revoke(&mut self, token_id: TokenId) -> Result<(), Error> {
+        self.assert_not_paused();
+        //Recognitions can be withdrawn, transferred grades belong to the school that issued them
+        if let Some(GradeOrigin::Transfer { .. }) = self.grade_origin(token_id) {
+            runtime::revert(NotenError::ExternalGrade);
+        }
+        if self.is_revoked(token_id) {
+            runtime::revert(NotenError::GradeRevoked);
+        }
+        if let Some(record) = self.grade_record(token_id) {
+            self.assert_term_open(record.year);
+            let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+            self.remove_from_total(owner, &record.subject, record.year, record.grade);
+            self.remove_from_exam(token_id, record.grade);
+        }
+        let mut meta = self.token_meta(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+        meta.insert("revoked".to_string(), "true".to_string());
+        Dict::instance(REVOKED_GRADES_DICT).set(&token_id.to_string(), true);
+        self.record_event("revoked", token_id);
+        self.set_token_meta(token_id, meta)
+    }
+    fn is_revoked(&self, token_id: TokenId) -> bool {
+        Dict::instance(REVOKED_GRADES_DICT).get(&token_id.to_string()).unwrap_or_default()
+    }
+    fn approval_threshold(&self) -> u32 {
+        get_key(APPROVAL_THRESHOLD_KEY).unwrap_or(1)
+    }
+    /// Sensitive actions can only be taken by a single administrator while the threshold is one.
+    fn assert_single_approval(&self) {
+        if self.approval_threshold() > 1 {
+            runtime::revert(NotenError::ApprovalRequired);
+        }
+    }
+    fn permanent_teacher_count(&self) -> u32 {
+        get_key(PERMANENT_TEACHER_COUNT_KEY).unwrap_or_default()
+    }
+    /// A threshold above the number of permanent teachers could never be reached again.
+    fn assert_valid_threshold(&self, threshold: u32) {
+        if threshold == 0 || threshold > self.permanent_teacher_count() {
+            runtime::revert(NotenError::InvalidThreshold);
+        }
+    }
+    /// Keeps count of the teachers without an appointment. Reverts if one would leave fewer of them
+    /// than the approval threshold.
+    fn set_permanent(&mut self, teacher: Key, permanent: bool) {
+        let counted: bool = Dict::instance(PERMANENT_TEACHERS_DICT).get(&key_to_str(&teacher)).unwrap_or_default();
+        if counted == permanent {
+            return;
+        }
+        let count = self.permanent_teacher_count();
+        let count = if permanent {
+            count + 1
+        } else {
+            count - 1
+        };
+        if !permanent && count < self.approval_threshold() {
+            runtime::revert(NotenError::InvalidThreshold);
+        }
+        Dict::instance(PERMANENT_TEACHERS_DICT).set(&key_to_str(&teacher), permanent);
+        set_key(PERMANENT_TEACHER_COUNT_KEY, count);
+    }
+    /// Creates a proposal counting the proposer's approval; it executes right away if that is enough.
+    fn propose(&mut self, action: Action, deadline: u64) -> u32 {
+        self.assert_permanent_teacher();
+        match &action {
+            Action::SetApprovalThreshold(threshold) => self.assert_valid_threshold(*threshold),
+            Action::AppointTeacher(_, appointment) => self.assert_valid_appointment(appointment),
+            _ => {}
+        }
+        let proposal_id: u32 = get_key(PROPOSAL_COUNT_KEY).unwrap_or_default();
+        set_key(PROPOSAL_COUNT_KEY, proposal_id + 1);
+        let proposal = Proposal {
+            action,
+            proposer: self.get_caller(),
+            deadline,
+            approvals: Vec::new(),
+            executed: false,
+        };
+        Dict::instance(PROPOSALS_DICT).set(&proposal_id.to_string(), proposal);
+        self.approve_proposal(proposal_id);
+        proposal_id
+    }
+    fn approve_proposal(&mut self, proposal_id: u32) {
+        self.assert_permanent_teacher();
+        let mut proposal = self.proposal(proposal_id).unwrap_or_revert_with(NotenError::UnknownProposal);
+        if proposal.executed {
+            runtime::revert(NotenError::ProposalExecuted);
+        }
+        let now: u64 = runtime::get_blocktime().into();
+        if now > proposal.deadline {
+            runtime::revert(NotenError::ProposalExpired);
+        }
+        let approver = self.get_caller();
+        if proposal.approvals.contains(&approver) {
+            runtime::revert(NotenError::AlreadyApproved);
+        }
+        proposal.approvals.push(approver);
+        //Approvals of administrators who have been removed or appointed in the meantime don't count
+        let approvals = proposal.approvals.iter().filter(|admin| self.is_permanent_teacher(**admin)).count() as u32;
+        if approvals >= self.required_approvals(&proposal.action) {
+            proposal.executed = true;
+            self.execute(proposal.action.clone());
+        }
+        Dict::instance(PROPOSALS_DICT).set(&proposal_id.to_string(), proposal);
+    }
+    fn execute(&mut self, action: Action) {
+        match action {
+            Action::RemoveTeacher(teacher) => self.disable_teacher(teacher),
+            Action::RevokeGrade(token_id) => self.revoke(token_id).unwrap_or_revert(),
+            Action::SetApprovalThreshold(threshold) => {
+                //Teachers may have been removed since the proposal was made
+                self.assert_valid_threshold(threshold);
+                set_key(APPROVAL_THRESHOLD_KEY, threshold);
+            }
+            Action::Unpause => set_key(PAUSED_KEY, false),
+            Action::ReopenTerm(year) => {
+                self.assert_not_paused();
+                Dict::instance(CLOSED_TERMS_DICT).set(&year.to_string(), false);
+            }
+            Action::AddTeacher(teacher) => self.make_teacher(teacher),
+            Action::AppointTeacher(teacher, appointment) => self.appoint(teacher, appointment),
+        }
+    }
+    fn is_paused(&self) -> bool {
+        get_key(PAUSED_KEY).unwrap_or_default()
+    }
+    fn assert_not_paused(&self) {
+        if self.is_paused() {
+            runtime::revert(NotenError::Paused);
+        }
+    }
+    /// Stops grading and new staff until unpaused, e.g. while a compromised teacher key is investigated.
+    /// Teachers can still be removed, so the compromised key can be taken out before unpausing.
+    /// Unpausing always takes a proposal, see `required_approvals`.
+    fn pause(&mut self) {
+        self.assert_permanent_teacher();
+        set_key(PAUSED_KEY, true);
+    }
+    /// A single key, possibly the compromised one, can't unpause while a second permanent teacher exists.
+    fn required_approvals(&self, action: &Action) -> u32 {
+        let threshold = self.approval_threshold();
+        match action {
+            Action::Unpause => threshold.max(self.permanent_teacher_count().min(2)),
+            _ => threshold,
+        }
+    }
+    fn proposal(&self, proposal_id: u32) -> Option<Proposal> {
+        Dict::instance(PROPOSALS_DICT).get(&proposal_id.to_string())
+    }
+    /// Teachers are `AdminControl` admins; appointed ones only while their appointment lasts.
+    fn is_teacher(&self, teacher: Key) -> bool {
+        if !self.is_admin(teacher) {
+            return false;
+        }
+        match self.appointment(teacher) {
+            Some(appointment) => appointment.is_active(runtime::get_blocktime().into()),
+            None => true,
+        }
+    }
+    fn assert_teacher(&self) {
+        if !self.is_teacher(self.get_caller()) {
+            runtime::revert(NotenError::NotTeacher);
+        }
+    }
+    fn assert_teacher_for(&self, subject: &str, student: Key) {
+        self.assert_teacher();
+        if let Some(appointment) = self.appointment(self.get_caller()) {
+            let class = self.student_class(self.student_account(student));
+            if !appointment.covers_subject(subject) || !appointment.covers_class(class.as_deref()) {
+                runtime::revert(NotenError::OutsideAppointment);
+            }
+        }
+    }
+    fn appointment(&self, teacher: Key) -> Option<Appointment> {
+        Dict::instance(APPOINTMENTS_DICT).get(&key_to_str(&teacher))
+    }
+    fn set_student_class(&mut self, student: Key, class: String) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        self.register_student(student);
+        Dict::instance(STUDENT_CLASSES_DICT).set(&key_to_str(&student), class);
+        self.record_staff_event("class_set", student);
+    }
+    /// Adds a student to the index the year rollover walks through.
+    fn register_student(&mut self, student: Key) {
+        let registered = Dict::instance(REGISTERED_STUDENTS_DICT);
+        if registered.get::<bool>(&key_to_str(&student)).unwrap_or_default() {
+            return;
+        }
+        let count: u64 = get_key(STUDENT_COUNT_KEY).unwrap_or_default();
+        registered.set(&key_to_str(&student), true);
+        Dict::instance(STUDENTS_DICT).set(&count.to_string(), student);
+        set_key(STUDENT_COUNT_KEY, count + 1);
+    }
+    fn grade_total(&self, student: Key, subject: &str, year: u32) -> GradeTotal {
+        Dict::instance(GRADE_TOTALS_DICT)
+            .get(&key_and_value_to_str(&student, &(subject.to_string(), year)))
+            .unwrap_or_default()
+    }
+    fn add_to_total(&mut self, student: Key, subject: &str, year: u32, grade: u32) {
+        let (sum, count) = self.grade_total(student, subject, year);
+        if count == 0 {
+            let subjects_key = key_and_value_to_str(&student, &year);
+            let mut subjects: Vec<String> = Dict::instance(STUDENT_SUBJECTS_DICT).get(&subjects_key).unwrap_or_default();
+            if !subjects.iter().any(|graded| graded == subject) {
+                subjects.push(subject.to_string());
+                Dict::instance(STUDENT_SUBJECTS_DICT).set(&subjects_key, subjects);
+            }
+        }
+        let total: GradeTotal = (sum + grade as u64, count + 1);
+        Dict::instance(GRADE_TOTALS_DICT).set(&key_and_value_to_str(&student, &(subject.to_string(), year)), total);
+    }
+    fn remove_from_total(&mut self, student: Key, subject: &str, year: u32, grade: u32) {
+        let (sum, count) = self.grade_total(student, subject, year);
+        let total: GradeTotal = (sum.saturating_sub(grade as u64), count.saturating_sub(1));
+        Dict::instance(GRADE_TOTALS_DICT).set(&key_and_value_to_str(&student, &(subject.to_string(), year)), total);
+    }
+    /// The running average of a student's grades, rounded to the grade scale's unit.
+    fn average(&self, student: Key, subject: &str, year: u32) -> Option<u32> {
+        total::average(self.grade_total(student, subject, year))
+    }
+    fn final_average(&self, student: Key, subject: &str, year: u32) -> Option<u32> {
+        Dict::instance(FINAL_AVERAGES_DICT).get(&key_and_value_to_str(&student, &(subject.to_string(), year)))
+    }
+    fn is_term_closed(&self, year: u32) -> bool {
+        Dict::instance(CLOSED_TERMS_DICT).get(&year.to_string()).unwrap_or_default()
+    }
+    fn assert_term_open(&self, year: u32) {
+        if self.is_term_closed(year) {
+            runtime::revert(NotenError::TermClosed);
+        }
+    }
+    fn reopen_term(&mut self, year: u32) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        self.assert_single_approval();
+        Dict::instance(CLOSED_TERMS_DICT).set(&year.to_string(), false);
+    }
+    fn set_final_class_level(&mut self, level: u32) {
+        self.assert_teacher();
+        self.assert_not_paused();
+        set_key(FINAL_CLASS_LEVEL_KEY, level);
+    }
+    /// Closes the school year, then works through at most `limit` students per call: their final
+    /// averages are recorded and they move up a class ("2a" becomes "3a") or graduate from the
+    /// final class level. The call that completes the year records `archive` and returns true.
+    /// A year is rolled over only once, even if its term is reopened afterwards.
+    fn rollover_year(&mut self, year: u32, archive: String, limit: u32) -> bool {
+        self.assert_permanent_teacher();
+        self.assert_not_paused();
+        if limit == 0 {
+            runtime::revert(NotenError::InvalidLimit);
+        }
+        if self.year_archive(year).is_some() {
+            runtime::revert(NotenError::YearArchived);
+        }
+        match get_key::<Option<u32>>(ROLLOVER_YEAR_KEY).flatten() {
+            Some(in_progress) if in_progress != year => runtime::revert(NotenError::RolloverInProgress),
+            Some(_) => {}
+            None => {
+                Dict::instance(CLOSED_TERMS_DICT).set(&year.to_string(), true);
+                set_key(ROLLOVER_YEAR_KEY, Some(year));
+                set_key(ROLLOVER_CURSOR_KEY, 0u64);
+            }
+        }
+
+        let student_count: u64 = get_key(STUDENT_COUNT_KEY).unwrap_or_default();
+        let cursor: u64 = get_key(ROLLOVER_CURSOR_KEY).unwrap_or_default();
+        let end = student_count.min(cursor + limit as u64);
+        let final_level: u32 = get_key(FINAL_CLASS_LEVEL_KEY).unwrap_or_default();
+        for index in cursor..end {
+            let student: Key = Dict::instance(STUDENTS_DICT).get(&index.to_string()).unwrap_or_revert();
+            self.close_student_year(student, year, final_level);
+        }
+        set_key(ROLLOVER_CURSOR_KEY, end);
+        if end < student_count {
+            return false;
+        }
+
+        let closed_at: u64 = runtime::get_blocktime().into();
+        let snapshot: YearArchive = (archive, closed_at, student_count);
+        Dict::instance(YEAR_ARCHIVES_DICT).set(&year.to_string(), snapshot);
+        set_key(ROLLOVER_YEAR_KEY, Option::<u32>::None);
+        true
+    }
+    fn close_student_year(&mut self, student: Key, year: u32, final_level: u32) {
+        if self.graduation(student).is_some() {
+            return;
+        }
+        let subjects: Vec<String> = Dict::instance(STUDENT_SUBJECTS_DICT)
+            .get(&key_and_value_to_str(&student, &year))
+            .unwrap_or_default();
+        for subject in subjects {
+            if let Some(average) = self.average(student, &subject, year) {
+                Dict::instance(FINAL_AVERAGES_DICT).set(&key_and_value_to_str(&student, &(subject, year)), average);
+            }
+        }
+        // Students without a numbered class keep it, but their final averages are still logged.
+        let promotion = self.student_class(student).and_then(|class| {
+            let digits = class.chars().take_while(|c| c.is_ascii_digit()).count();
+            class[..digits].parse::<u32>().ok().map(|level| (level, class[digits..].to_string()))
+        });
+        match promotion {
+            Some((level, _)) if level >= final_level => {
+                Dict::instance(GRADUATES_DICT).set(&key_to_str(&student), year);
+                self.record_staff_event("graduated", student);
+            }
+            Some((level, stream)) => {
+                Dict::instance(STUDENT_CLASSES_DICT).set(&key_to_str(&student), format!("{}{}", level + 1, stream));
+                self.record_staff_event("class_set", student);
+            }
+            None => self.record_staff_event("class_set", student),
+        }
+    }
+    /// The school year in which a student graduated.
+    fn graduation(&self, student: Key) -> Option<u32> {
+        Dict::instance(GRADUATES_DICT).get(&key_to_str(&student))
+    }
+    fn year_archive(&self, year: u32) -> Option<YearArchive> {
+        Dict::instance(YEAR_ARCHIVES_DICT).get(&year.to_string())
+    }
+    fn student_class(&self, student: Key) -> Option<String> {
+        Dict::instance(STUDENT_CLASSES_DICT).get(&key_to_str(&student))
+    }
+    fn remove_teacher(&mut self, teacher: Key) {
+        self.assert_permanent_teacher();
+        self.assert_single_approval();
+        self.disable_teacher(teacher);
+    }
+    fn disable_teacher(&mut self, teacher: Key) {
+        self.set_permanent(teacher, false);
+        self.disable_admin(teacher);
+        self.record_staff_event("teacher_removed", teacher);
+    }
+
+    /// Teachers whose rights lapse, or are limited to some subjects, can't hand out lasting or wider ones.
+    fn assert_permanent_teacher(&self) {
+        self.assert_teacher();
+        if !self.is_permanent_teacher(self.get_caller()) {
+            runtime::revert(NotenError::NotPermanentTeacher);
+        }
+    }
+    fn is_permanent_teacher(&self, teacher: Key) -> bool {
+        self.is_teacher(teacher) && self.appointment(teacher).is_none()
+    }
+
+    /// Adds a permanent teacher; needs a proposal if a threshold is configured.
+    fn add_teacher(&mut self, teacher: Key) {
+        self.assert_permanent_teacher();
+        self.assert_single_approval();
+        self.make_teacher(teacher);
+    }
+    fn make_teacher(&mut self, teacher: Key) {
+        self.assert_not_paused();
+        Dict::instance(APPOINTMENTS_DICT).remove::<Appointment>(&key_to_str(&teacher));
+        self.add_admin(teacher);
+        self.set_permanent(teacher, true);
+        self.record_staff_event("teacher_added", teacher);
+    }
+
+    /// Adds a teacher whose rights lapse at `end` and can be limited to some subjects and classes;
+    /// needs a proposal if a threshold is configured.
+    fn appoint_teacher(&mut self, teacher: Key, appointment: Appointment) {
+        self.assert_permanent_teacher();
+        self.assert_single_approval();
+        self.appoint(teacher, appointment);
+    }
+    fn appoint(&mut self, teacher: Key, appointment: Appointment) {
+        self.assert_not_paused();
+        self.assert_valid_appointment(&appointment);
+        self.set_permanent(teacher, false);
+        Dict::instance(APPOINTMENTS_DICT).set(&key_to_str(&teacher), appointment);
+        self.add_admin(teacher);
+        self.record_staff_event("teacher_appointed", teacher);
+    }
+    fn assert_valid_appointment(&self, appointment: &Appointment) {
+        if appointment.end <= appointment.start {
+            runtime::revert(NotenError::InvalidAppointment);
+        }
+    }
+    /// Records the given grades of a student who moves to the school with the contract package `destination`.
+    fn issue_transfer(&mut self, student: Key, token_ids: Vec<TokenId>, destination: Key) -> u32 {
+        self.assert_teacher();
+        self.assert_not_paused();
+        if destination.into_hash().is_none() {
+            runtime::revert(NotenError::InvalidSchool);
+        }
+        let mut grades = Vec::new();
+        for token_id in token_ids {
+            let owner = self.owner_of(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+            if owner != student {
+                runtime::revert(NotenError::StudentMismatch);
+            }
+            if self.is_revoked(token_id) {
+                runtime::revert(NotenError::GradeRevoked);
+            }
+            let record = self.grade_record(token_id).unwrap_or_revert_with(NotenError::MalformedGrade);
+            grades.push((token_id, record));
+        }
+        let transfer = Transfer {
+            student,
+            destination,
+            grades,
+            issued_by: self.get_caller(),
+            issued_at: runtime::get_blocktime().into(),
+        };
+        let transfer_id: u32 = get_key(TRANSFER_COUNT_KEY).unwrap_or_default();
+        Dict::instance(TRANSFERS_DICT).set(&transfer_id.to_string(), transfer);
+        set_key(TRANSFER_COUNT_KEY, transfer_id + 1);
+        transfer_id
+    }
+    fn transfer_record(&self, transfer_id: u32) -> Option<Transfer> {
+        Dict::instance(TRANSFERS_DICT).get(&transfer_id.to_string())
+    }
+    /// Reads a transfer record from the sending school's contract and mints its grades as
+    /// external grades of `student`, who may be known under a different key at this school.
+    /// The canton registry whose active schools transfers are accepted from.
+    fn set_registry(&mut self, registry: Key) {
+        self.assert_permanent_teacher();
+        self.assert_not_paused();
+        if registry.into_hash().is_none() {
+            runtime::revert(NotenError::InvalidSchool);
+        }
+        set_key(REGISTRY_KEY, Some(registry));
+    }
+    /// Imports the transfer `transfer_id` of `school`, which has to be an active school of the registry
+    /// set with `set_registry`: any contract could claim to be a school and hand out grades otherwise.
+    fn import_transfer(&mut self, school: Key, transfer_id: u32, student: Key) -> Vec<TokenId> {
+        self.assert_teacher();
+        self.assert_not_paused();
+        let school_package = ContractPackageHash::new(school.into_hash().unwrap_or_revert_with(NotenError::InvalidSchool));
+        let registry = get_key::<Option<Key>>(REGISTRY_KEY).flatten().unwrap_or_revert_with(NotenError::UnregisteredSchool);
+        let registry = ContractPackageHash::new(registry.into_hash().unwrap_or_revert());
+        let entry: Option<(String, Meta, bool)> =
+            runtime::call_versioned_contract(registry, None, "school", runtime_args! { "school" => school });
+        if !matches!(entry, Some((_, _, true))) {
+            runtime::revert(NotenError::UnregisteredSchool);
+        }
+        let imported = key_and_value_to_str(&school, &transfer_id);
+        if Dict::instance(IMPORTED_TRANSFERS_DICT).get::<bool>(&imported).unwrap_or_default() {
+            runtime::revert(NotenError::TransferImported);
+        }
+        let transfer: Option<Transfer> = runtime::call_versioned_contract(
+            school_package,
+            None,
+            "transfer_record",
+            runtime_args! { "transfer_id" => transfer_id },
+        );
+        let transfer = transfer.unwrap_or_revert_with(NotenError::UnknownTransfer);
+        if transfer.destination != self.self_addr() {
+            runtime::revert(NotenError::WrongDestination);
+        }
+        Dict::instance(IMPORTED_TRANSFERS_DICT).set(&imported, true);
+        if transfer.grades.is_empty() {
+            return Vec::new();
+        }
+
+        let list_of_meta = transfer.grades.iter()
+            .map(|(token_id, record)| {
+                let mut meta = Meta::new();
+                meta.insert("subject".to_string(), record.subject.clone());
+                meta.insert("year".to_string(), record.year.to_string());
+                meta.insert("grade_type".to_string(), record.grade_type.clone());
+                meta.insert("grade".to_string(), record.grade.to_string());
+                meta.insert("teacher".to_string(), record.teacher.to_formatted_string());
+                meta.insert("issued_at".to_string(), record.issued_at.to_string());
+                meta.insert("contract_version".to_string(), record.contract_version.to_string());
+                meta.insert("kind".to_string(), "external".to_string());
+                meta.insert("school".to_string(), school.to_formatted_string());
+                meta.insert("school_token_id".to_string(), token_id.to_string());
+                meta
+            })
+            .collect();
+        let token_ids = self.mint(student, Option::None, list_of_meta).unwrap_or_revert();
+        self.register_student(student);
+        for (token_id, (school_token_id, record)) in token_ids.iter().zip(transfer.grades) {
+            let origin = GradeOrigin::Transfer { school, transfer_id, token_id: school_token_id };
+            Dict::instance(GRADE_ORIGINS_DICT).set(&token_id.to_string(), origin);
+            let record = GradeRecord { student, ..record };
+            Dict::instance(GRADE_RECORDS_DICT).set(&token_id.to_string(), record);
+            self.index_grade(*token_id);
+            self.record_event("imported", *token_id);
+        }
+        token_ids
+    }
+    /// Records a grade earned at another institution, converted to the local scale as `grade`.
+    fn recognise_grade(&mut self, student: Key, subject: String, year: u32, grade: u32, recognition: Recognition) -> Vec<TokenId> {
+        self.assert_teacher_for(&subject, student);
+        self.assert_not_paused();
+        self.assert_term_open(year);
+        self.assert_on_scale(grade);
+        let teacher = self.get_caller();
+        let issued_at: u64 = runtime::get_blocktime().into();
+        let mut meta = Meta::new();
+        meta.insert("subject".to_string(), subject.clone());
+        meta.insert("year".to_string(), year.to_string());
+        meta.insert("grade_type".to_string(), "recognition".to_string());
+        meta.insert("grade".to_string(), grade.to_string());
+        meta.insert("teacher".to_string(), teacher.to_formatted_string());
+        meta.insert("issued_at".to_string(), issued_at.to_string());
+        meta.insert("contract_version".to_string(), self.contract_version().to_string());
+        meta.insert("kind".to_string(), "recognised".to_string());
+        meta.insert("institution".to_string(), recognition.institution.clone());
+        meta.insert("original_scale".to_string(), recognition.scale.clone());
+        meta.insert("original_value".to_string(), recognition.value.clone());
+        meta.insert("conversion_rule".to_string(), recognition.rule.clone());
+
+        let token_ids = self.mint(student, Option::None, vec![meta]).unwrap_or_revert();
+        self.register_student(student);
+        self.add_to_total(student, &subject, year, grade);
+        let record = GradeRecord {
+            student,
+            subject,
+            year,
+            grade_type: "recognition".to_string(),
+            grade,
+            teacher,
+            issued_at,
+            contract_version: self.contract_version(),
+        };
+        for token_id in &token_ids {
+            Dict::instance(GRADE_RECORDS_DICT).set(&token_id.to_string(), record.clone());
+            Dict::instance(GRADE_ORIGINS_DICT).set(&token_id.to_string(), GradeOrigin::Recognition(recognition.clone()));
+            self.index_grade(*token_id);
+            self.record_event("recognised", *token_id);
+        }
+        token_ids
+    }
+    fn grading_scale(&self) -> GradingScale {
+        get_key(GRADING_SCALE_KEY).unwrap_or_default()
+    }
+    /// The scale can only change before the first grade or exam, so exam histograms, which are
+    /// sized when the exam is registered, always match it.
+    fn set_grading_scale(&mut self, scale: GradingScale) {
+        self.assert_permanent_teacher();
+        self.assert_not_paused();
+        if !scale.is_valid() {
+            runtime::revert(NotenError::InvalidScale);
+        }
+        if get_key::<u64>(GRADE_COUNT_KEY).unwrap_or_default() > 0 || get_key::<u32>(EXAM_COUNT_KEY).unwrap_or_default() > 0 {
+            runtime::revert(NotenError::ScaleInUse);
+        }
+        set_key(GRADING_SCALE_KEY, scale);
+    }
+    fn assert_on_scale(&self, grade: u32) {
+        if !self.grading_scale().contains(grade) {
+            runtime::revert(NotenError::GradeOutOfScale);
+        }
+    }
+    fn register_exam(&mut self, exam: Exam) -> u32 {
+        self.assert_teacher();
+        self.assert_not_paused();
+        self.assert_term_open(exam.year);
+        if let Some(appointment) = self.appointment(self.get_caller()) {
+            if !appointment.covers_subject(&exam.subject) || !appointment.covers_class(Some(&exam.class)) {
+                runtime::revert(NotenError::OutsideAppointment);
+            }
+        }
+        let exam_id: u32 = get_key(EXAM_COUNT_KEY).unwrap_or_default();
+        Dict::instance(EXAMS_DICT).set(&exam_id.to_string(), exam);
+        Dict::instance(EXAM_STATS_DICT).set(&exam_id.to_string(), ExamStats::new(&self.grading_scale()));
+        set_key(EXAM_COUNT_KEY, exam_id + 1);
+        exam_id
+    }
+    fn exam(&self, exam_id: u32) -> Option<Exam> {
+        Dict::instance(EXAMS_DICT).get(&exam_id.to_string())
+    }
+    fn exam_summary(&self, exam_id: u32) -> ExamSummary {
+        let stats: ExamStats = Dict::instance(EXAM_STATS_DICT).get(&exam_id.to_string())
+            .unwrap_or_revert_with(NotenError::UnknownExam);
+        stats.summary(&self.grading_scale())
+    }
+    fn add_to_exam(&mut self, token_id: TokenId, grade: u32) {
+        if let Some(exam_id) = Dict::instance(GRADE_EXAMS_DICT).get::<u32>(&token_id.to_string()) {
+            let stats = Dict::instance(EXAM_STATS_DICT);
+            let mut exam_stats: ExamStats = stats.get(&exam_id.to_string()).unwrap_or_revert();
+            exam_stats.add(&self.grading_scale(), grade);
+            stats.set(&exam_id.to_string(), exam_stats);
+        }
+    }
+    fn remove_from_exam(&mut self, token_id: TokenId, grade: u32) {
+        if let Some(exam_id) = Dict::instance(GRADE_EXAMS_DICT).get::<u32>(&token_id.to_string()) {
+            let stats = Dict::instance(EXAM_STATS_DICT);
+            let mut exam_stats: ExamStats = stats.get(&exam_id.to_string()).unwrap_or_revert();
+            exam_stats.remove(&self.grading_scale(), grade);
+            stats.set(&exam_id.to_string(), exam_stats);
+        }
+    }
+    /// Appends a grade to the index of all grades, in the order they were issued.
+    fn index_grade(&mut self, token_id: TokenId) {
+        let position: u64 = get_key(GRADE_COUNT_KEY).unwrap_or_default();
+        Dict::instance(GRADES_DICT).set(&position.to_string(), token_id);
+        Dict::instance(GRADE_POSITIONS_DICT).set(&token_id.to_string(), position);
+        set_key(GRADE_COUNT_KEY, position + 1);
+    }
+    fn grade_position(&self, token_id: TokenId) -> Option<u64> {
+        Dict::instance(GRADE_POSITIONS_DICT).get(&token_id.to_string())
+    }
+    /// Appends to the event log, which records every change to a grade so reports can catch up
+    /// from the last event they've seen.
+    fn record_event(&mut self, event: &str, token_id: TokenId) {
+        let index: u64 = get_key(EVENT_COUNT_KEY).unwrap_or_default();
+        let time: u64 = runtime::get_blocktime().into();
+        let mut entry = Meta::new();
+        entry.insert("event".to_string(), event.to_string());
+        entry.insert("token_id".to_string(), token_id.to_string());
+        entry.insert("time".to_string(), time.to_string());
+        Dict::instance(EVENTS_DICT).set(&index.to_string(), entry);
+        set_key(EVENT_COUNT_KEY, index + 1);
+    }
+    /// Logs changes to teachers and classes, including the rollover's promotions and graduations,
+    /// with the account they concern, so off-chain mirrors
+    /// can follow them like they follow grades.
+    fn record_staff_event(&mut self, event: &str, account: Key) {
+        let index: u64 = get_key(STAFF_EVENT_COUNT_KEY).unwrap_or_default();
+        let time: u64 = runtime::get_blocktime().into();
+        let mut entry = Meta::new();
+        entry.insert("event".to_string(), event.to_string());
+        entry.insert("account".to_string(), account.to_formatted_string());
+        entry.insert("time".to_string(), time.to_string());
+        Dict::instance(STAFF_EVENTS_DICT).set(&index.to_string(), entry);
+        set_key(STAFF_EVENT_COUNT_KEY, index + 1);
+    }
+    /// Token ids of the grades at `offset` to `offset + limit` in the grade index, at most `MAX_PAGE_SIZE`.
+    fn grades_page(&self, offset: u64, limit: u64) -> Vec<TokenId> {
+        page(offset, limit, get_key(GRADE_COUNT_KEY).unwrap_or_default())
+            .map(|position| Dict::instance(GRADES_DICT).get(&position.to_string()).unwrap_or_revert())
+            .collect()
+    }
+    /// Events from `event_index` on, at most `MAX_PAGE_SIZE` of them.
+    fn grades_since(&self, event_index: u64, limit: u64) -> Vec<Meta> {
+        page(event_index, limit, get_key(EVENT_COUNT_KEY).unwrap_or_default())
+            .map(|index| Dict::instance(EVENTS_DICT).get(&index.to_string()).unwrap_or_revert())
+            .collect()
+    }
+    /// Staff log entries from `event_index` on, at most `MAX_PAGE_SIZE`.
+    fn staff_events_since(&self, event_index: u64, limit: u64) -> Vec<Meta> {
+        page(event_index, limit, get_key(STAFF_EVENT_COUNT_KEY).unwrap_or_default())
+            .map(|index| Dict::instance(STAFF_EVENTS_DICT).get(&index.to_string()).unwrap_or_revert())
+            .collect()
+    }
+    /// `None` for grades this school issued itself.
+    fn grade_origin(&self, token_id: TokenId) -> Option<GradeOrigin> {
+        Dict::instance(GRADE_ORIGINS_DICT).get(&token_id.to_string())
+    }
+    fn assert_issued_here(&self, token_id: TokenId) {
+        if self.grade_origin(token_id).is_some() {
+            runtime::revert(NotenError::ExternalGrade);
+        }
+    }
+}
+
+#[no_mangle]
+fn constructor() {
+    let name = runtime::get_named_arg::<String>("name");
+    let symbol = runtime::get_named_arg::<String>("symbol");
+    let meta = runtime::get_named_arg::<Meta>("meta");
+    let admin = runtime::get_named_arg::<Key>("admin");
+    NotenContract::default().constructor(name, symbol, meta);
+    NotenContract::default().add_admin_without_checked(admin);
+    NotenContract::default().set_permanent(admin, true);
+    NotenContract::default().record_staff_event("teacher_added", admin);
+}
+
+#[no_mangle]
+fn name() {
+    let ret = NotenContract::default().name();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn symbol() {
+    let ret = NotenContract::default().symbol();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+fn meta() {
+    let ret = NotenContract::default().meta();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+teachers can give grades to students
+ */
+#[no_mangle]
+pub extern "C" fn grade() {
+    let student:Key  = runtime::get_named_arg("student");
+    let subject:String = runtime::get_named_arg("subject");
+    let year:u32 = runtime::get_named_arg("year");
+    let grade_type:String = runtime::get_named_arg("type");
+    let grade:u32 = runtime::get_named_arg("grade");
+    NotenContract::default().grade(student, subject, year, grade_type, grade).unwrap_or_revert();
+}
+/*
+exams are registered for a class, their grades are aggregated for the principal
+ */
+#[no_mangle]
+pub extern "C" fn register_exam() {
+    let exam = Exam {
+        subject: runtime::get_named_arg("subject"),
+        class: runtime::get_named_arg("class"),
+        year: runtime::get_named_arg("year"),
+        date: runtime::get_named_arg("date"),
+        exam_type: runtime::get_named_arg("type"),
+    };
+    let ret = NotenContract::default().register_exam(exam);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grade_exam() {
+    let student: Key = runtime::get_named_arg("student");
+    let exam_id: u32 = runtime::get_named_arg("exam_id");
+    let grade: u32 = runtime::get_named_arg("grade");
+    let ret = NotenContract::default().grade_exam(student, exam_id, grade).unwrap_or_revert();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn exam() {
+    let exam_id: u32 = runtime::get_named_arg("exam_id");
+    let ret = NotenContract::default().exam(exam_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn exam_summary() {
+    let exam_id: u32 = runtime::get_named_arg("exam_id");
+    let ret = NotenContract::default().exam_summary(exam_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grading_scale() {
+    let ret = NotenContract::default().grading_scale();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_grading_scale() {
+    let scale = GradingScale {
+        min: runtime::get_named_arg("min"),
+        max: runtime::get_named_arg("max"),
+        step: runtime::get_named_arg("step"),
+    };
+    NotenContract::default().set_grading_scale(scale);
+}
+
+/*
+teachers can update/change the grade
+ */
+#[no_mangle]
+pub extern "C" fn update_grade() {
+    let student: Key = runtime::get_named_arg("student");
+    let grade: u32 = runtime::get_named_arg("grade");
+    let token_id:TokenId = runtime::get_named_arg("token_id");
+    NotenContract::default().update_grade(student, grade, token_id).unwrap_or_revert();
+}
+/*
+teachers can be removed, so they cant issue grades anymore
+ */
+#[no_mangle]
+pub extern "C" fn remove_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    NotenContract::default().remove_teacher(teacher);
+}
+/*
+revoked grades stay on record but no longer count, needs approval if a threshold is configured
+ */
+#[no_mangle]
+pub extern "C" fn revoke_grade() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    NotenContract::default().revoke_grade(token_id).unwrap_or_revert();
+}
+/*
+administrators propose sensitive actions, which execute once enough administrators approved them
+ */
+#[no_mangle]
+pub extern "C" fn propose_remove_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::RemoveTeacher(teacher), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn propose_revoke_grade() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::RevokeGrade(token_id), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn propose_approval_threshold() {
+    let threshold: u32 = runtime::get_named_arg("threshold");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::SetApprovalThreshold(threshold), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn propose_add_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::AddTeacher(teacher), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn propose_appoint_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    let appointment = Appointment {
+        start: runtime::get_named_arg("start"),
+        end: runtime::get_named_arg("end"),
+        subjects: runtime::get_named_arg("subjects"),
+        classes: runtime::get_named_arg("classes"),
+    };
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::AppointTeacher(teacher, appointment), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn approve_proposal() {
+    let proposal_id: u32 = runtime::get_named_arg("proposal_id");
+    NotenContract::default().approve_proposal(proposal_id);
+}
+
+#[no_mangle]
+pub extern "C" fn proposal() {
+    let proposal_id: u32 = runtime::get_named_arg("proposal_id");
+    let ret = NotenContract::default().proposal(proposal_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+administrators can stop all grading at once, and resume it once a second administrator agrees
+ */
+#[no_mangle]
+pub extern "C" fn pause() {
+    NotenContract::default().pause();
+}
+
+#[no_mangle]
+pub extern "C" fn propose_unpause() {
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::Unpause, deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+teachers can be added, so they can issue grades
+ */
+#[no_mangle]
+pub extern "C" fn add_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    NotenContract::default().add_teacher(teacher);
+}
+
+/*
+teachers can attach a payload to a grade, encrypted to the student (and their guardians)
+ */
+#[no_mangle]
+pub extern "C" fn set_grade_payload() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let recipients: Vec<PublicKey> = runtime::get_named_arg("recipients");
+    let payload: Bytes = runtime::get_named_arg("payload");
+    NotenContract::default().set_grade_payload(token_id, recipients, payload);
+}
+
+#[no_mangle]
+pub extern "C" fn grade_payload() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let ret = NotenContract::default().grade_payload(token_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+teachers can register the commitment a student needs to claim their pseudonym
+ */
+#[no_mangle]
+pub extern "C" fn link_pseudonym() {
+    let pseudonym: Key = runtime::get_named_arg("pseudonym");
+    let commitment: [u8; 32] = runtime::get_named_arg("commitment");
+    NotenContract::default().link_pseudonym(pseudonym, commitment);
+}
+/*
+students bind their account to the pseudonym their grades were issued to, publicly
+ */
+#[no_mangle]
+pub extern "C" fn claim_pseudonym() {
+    let pseudonym: Key = runtime::get_named_arg("pseudonym");
+    let nonce: String = runtime::get_named_arg("nonce");
+    NotenContract::default().claim_pseudonym(pseudonym, nonce);
+}
+
+#[no_mangle]
+pub extern "C" fn pseudonym_account() {
+    let pseudonym: Key = runtime::get_named_arg("pseudonym");
+    let ret = NotenContract::default().pseudonym_account(pseudonym);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+guardians can be linked to students, so they can read and acknowledge their grades
+ */
+#[no_mangle]
+pub extern "C" fn add_guardian() {
+    let student: Key = runtime::get_named_arg("student");
+    let guardian: Key = runtime::get_named_arg("guardian");
+    NotenContract::default().add_guardian(student, guardian);
+}
+
+#[no_mangle]
+pub extern "C" fn remove_guardian() {
+    let student: Key = runtime::get_named_arg("student");
+    let guardian: Key = runtime::get_named_arg("guardian");
+    NotenContract::default().remove_guardian(student, guardian);
+}
+
+#[no_mangle]
+pub extern "C" fn guardians() {
+    let student: Key = runtime::get_named_arg("student");
+    let ret = NotenContract::default().guardians(student);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+guardians sign off that they have seen a grade
+ */
+#[no_mangle]
+pub extern "C" fn acknowledge_grade() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    NotenContract::default().acknowledge_grade(token_id);
+}
+/*
+teachers can check when a guardian acknowledged a grade
+ */
+#[no_mangle]
+pub extern "C" fn acknowledgement() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let guardian: Key = runtime::get_named_arg("guardian");
+    let ret = NotenContract::default().acknowledgement(token_id, guardian);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+students and their guardians can dispute a grade
+ */
+#[no_mangle]
+pub extern "C" fn file_appeal() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let reason: String = runtime::get_named_arg("reason");
+    NotenContract::default().file_appeal(token_id, reason);
+}
+/*
+administrators and the review board take an open appeal under review
+ */
+#[no_mangle]
+pub extern "C" fn review_appeal() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    NotenContract::default().review_appeal(token_id);
+}
+/*
+an upheld appeal replaces the disputed grade
+ */
+#[no_mangle]
+pub extern "C" fn uphold_appeal() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let grade: u32 = runtime::get_named_arg("grade");
+    NotenContract::default().uphold_appeal(token_id, grade).unwrap_or_revert();
+}
+
+#[no_mangle]
+pub extern "C" fn reject_appeal() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    NotenContract::default().reject_appeal(token_id);
+}
+
+#[no_mangle]
+pub extern "C" fn appeal() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let ret = NotenContract::default().appeal(token_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+administrators designate the review board members
+ */
+#[no_mangle]
+pub extern "C" fn add_reviewer() {
+    let reviewer: Key = runtime::get_named_arg("reviewer");
+    NotenContract::default().add_reviewer(reviewer);
+}
+
+#[no_mangle]
+pub extern "C" fn remove_reviewer() {
+    let reviewer: Key = runtime::get_named_arg("reviewer");
+    NotenContract::default().remove_reviewer(reviewer);
+}
+
+/*
+substitutes get an appointment, so their rights lapse automatically
+ */
+#[no_mangle]
+pub extern "C" fn appoint_teacher() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    let appointment = Appointment {
+        start: runtime::get_named_arg("start"),
+        end: runtime::get_named_arg("end"),
+        subjects: runtime::get_named_arg("subjects"),
+        classes: runtime::get_named_arg("classes"),
+    };
+    NotenContract::default().appoint_teacher(teacher, appointment);
+}
+
+#[no_mangle]
+pub extern "C" fn appointment() {
+    let teacher: Key = runtime::get_named_arg("teacher");
+    let ret = NotenContract::default().appointment(teacher);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+students are assigned to a class, which appointments can be limited to
+ */
+#[no_mangle]
+pub extern "C" fn set_student_class() {
+    let student: Key = runtime::get_named_arg("student");
+    let class: String = runtime::get_named_arg("class");
+    NotenContract::default().set_student_class(student, class);
+}
+
+#[no_mangle]
+pub extern "C" fn student_class() {
+    let student: Key = runtime::get_named_arg("student");
+    let ret = NotenContract::default().student_class(student);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+at the end of the school year, terms are closed, averages recorded and classes promoted
+ */
+#[no_mangle]
+pub extern "C" fn rollover_year() {
+    let year: u32 = runtime::get_named_arg("year");
+    let archive: String = runtime::get_named_arg("archive");
+    let limit: u32 = runtime::get_named_arg("limit");
+    let ret = NotenContract::default().rollover_year(year, archive, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn set_final_class_level() {
+    let level: u32 = runtime::get_named_arg("level");
+    NotenContract::default().set_final_class_level(level);
+}
+/*
+closed terms can be reopened, with approval if a threshold is configured
+ */
+#[no_mangle]
+pub extern "C" fn reopen_term() {
+    let year: u32 = runtime::get_named_arg("year");
+    NotenContract::default().reopen_term(year);
+}
+
+#[no_mangle]
+pub extern "C" fn propose_reopen_term() {
+    let year: u32 = runtime::get_named_arg("year");
+    let deadline: u64 = runtime::get_named_arg("deadline");
+    let ret = NotenContract::default().propose(Action::ReopenTerm(year), deadline);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn average() {
+    let student: Key = runtime::get_named_arg("student");
+    let subject: String = runtime::get_named_arg("subject");
+    let year: u32 = runtime::get_named_arg("year");
+    let ret = NotenContract::default().average(student, &subject, year);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn final_average() {
+    let student: Key = runtime::get_named_arg("student");
+    let subject: String = runtime::get_named_arg("subject");
+    let year: u32 = runtime::get_named_arg("year");
+    let ret = NotenContract::default().final_average(student, &subject, year);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn graduation() {
+    let student: Key = runtime::get_named_arg("student");
+    let ret = NotenContract::default().graduation(student);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn year_archive() {
+    let year: u32 = runtime::get_named_arg("year");
+    let ret = NotenContract::default().year_archive(year);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+reports page through all grades, or through the changes since the last event they've seen
+ */
+#[no_mangle]
+pub extern "C" fn grades_page() {
+    let offset: u64 = runtime::get_named_arg("offset");
+    let limit: u64 = runtime::get_named_arg("limit");
+    let ret = NotenContract::default().grades_page(offset, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grades_since() {
+    let event_index: u64 = runtime::get_named_arg("event_index");
+    let limit: u64 = runtime::get_named_arg("limit");
+    let ret = NotenContract::default().grades_since(event_index, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn staff_events_since() {
+    let event_index: u64 = runtime::get_named_arg("event_index");
+    let limit: u64 = runtime::get_named_arg("limit");
+    let ret = NotenContract::default().staff_events_since(event_index, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn token_meta(){
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let ret = NotenContract::default().token_meta(token_id).unwrap_or_revert_with(NotenError::UnknownGrade);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grade_record() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let ret = NotenContract::default().grade_record(token_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+the sending school records the grades a student takes along, the receiving school imports them
+if the sending school is registered with its canton registry
+ */
+#[no_mangle]
+pub extern "C" fn set_registry() {
+    let registry: Key = runtime::get_named_arg("registry");
+    NotenContract::default().set_registry(registry);
+}
+
+#[no_mangle]
+pub extern "C" fn issue_transfer() {
+    let student: Key = runtime::get_named_arg("student");
+    let token_ids: Vec<TokenId> = runtime::get_named_arg("token_ids");
+    let destination: Key = runtime::get_named_arg("destination");
+    let ret = NotenContract::default().issue_transfer(student, token_ids, destination);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn transfer_record() {
+    let transfer_id: u32 = runtime::get_named_arg("transfer_id");
+    let ret = NotenContract::default().transfer_record(transfer_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn import_transfer() {
+    let school: Key = runtime::get_named_arg("school");
+    let transfer_id: u32 = runtime::get_named_arg("transfer_id");
+    let student: Key = runtime::get_named_arg("student");
+    let ret = NotenContract::default().import_transfer(school, transfer_id, student);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn recognise_grade() {
+    let student: Key = runtime::get_named_arg("student");
+    let subject: String = runtime::get_named_arg("subject");
+    let year: u32 = runtime::get_named_arg("year");
+    let grade: u32 = runtime::get_named_arg("grade");
+    let recognition = Recognition {
+        institution: runtime::get_named_arg("institution"),
+        scale: runtime::get_named_arg("original_scale"),
+        value: runtime::get_named_arg("original_value"),
+        rule: runtime::get_named_arg("conversion_rule"),
+    };
+    let ret = NotenContract::default().recognise_grade(student, subject, year, grade, recognition);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn grade_origin() {
+    let token_id: TokenId = runtime::get_named_arg("token_id");
+    let ret = NotenContract::default().grade_origin(token_id);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+/*
+converts grades of an upgraded package to the current storage format
+ */
+#[no_mangle]
+pub extern "C" fn migrate() {
+    let token_ids: Vec<TokenId> = runtime::get_named_arg("token_ids");
+    NotenContract::default().migrate(token_ids);
+}
+/*
+grades of a package that can't be upgraded are imported into a new install, then migrated
+ */
+#[no_mangle]
+pub extern "C" fn import_legacy_grade() {
+    let student: Key = runtime::get_named_arg("student");
+    let subject: String = runtime::get_named_arg("subject");
+    let year: u32 = runtime::get_named_arg("year");
+    let grade_type: String = runtime::get_named_arg("type");
+    let grade: u32 = runtime::get_named_arg("grade");
+    let source_package: Key = runtime::get_named_arg("source_package");
+    NotenContract::default().import_legacy_grade(student, subject, year, grade_type, grade, source_package).unwrap_or_revert();
+}
+
+/// Installs this code as the first version of a new package and constructs it with `admin` as the
+/// school's first teacher. Returns the package, its access uref, which upgrades need, the contract
+/// and the uref of its `contract_version`.
+pub fn install_school(name: String, symbol: String, meta: Meta, admin: Key) -> (ContractPackageHash, URef, ContractHash, URef) {
+    let (package_hash, access_uref) = storage::create_contract_package_at_hash();
+    let version_uref = storage::new_uref(ContractVersion::default());
+    let mut named_keys = NamedKeys::new();
+    named_keys.insert(CONTRACT_VERSION_KEY.to_string(), version_uref.into());
+    let (contract_hash, contract_version) = storage::add_contract_version(package_hash, get_entry_points(), named_keys);
+    storage::write(version_uref, contract_version);
+
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let constructor_args: RuntimeArgs = runtime_args! {
+        "name" => name,
+        "symbol" => symbol,
+        "meta" => meta,
+        "admin" => admin
+    };
+    let _: () = runtime::call_contract(contract_hash, "constructor", constructor_args);
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    (package_hash, access_uref, contract_hash, version_uref)
+}
+
+pub fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "constructor",
+        vec![
+            Parameter::new("name", String::cl_type()),
+            Parameter::new("symbol", String::cl_type()),
+            Parameter::new("meta", Meta::cl_type()),
+            Parameter::new("admin", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "name",
+        vec![],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "symbol",
+        vec![],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "meta",
+        vec![],
+        Meta::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "token_meta",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        Meta::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "migrate",
+        vec![Parameter::new("token_ids", Vec::<TokenId>::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "import_legacy_grade",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("subject", CLType::String),
+            Parameter::new("year", CLType::U32),
+            Parameter::new("type", CLType::String),
+            Parameter::new("grade", CLType::U32),
+            Parameter::new("source_package", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "register_exam",
+        vec![
+            Parameter::new("subject", CLType::String),
+            Parameter::new("class", CLType::String),
+            Parameter::new("year", CLType::U32),
+            Parameter::new("date", CLType::U64),
+            Parameter::new("type", CLType::String),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grade_exam",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("exam_id", CLType::U32),
+            Parameter::new("grade", CLType::U32),
+        ],
+        Vec::<TokenId>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "exam",
+        vec![Parameter::new("exam_id", CLType::U32)],
+        Option::<Exam>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "exam_summary",
+        vec![Parameter::new("exam_id", CLType::U32)],
+        ExamSummary::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grading_scale",
+        vec![],
+        GradingScale::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_grading_scale",
+        vec![
+            Parameter::new("min", CLType::U32),
+            Parameter::new("max", CLType::U32),
+            Parameter::new("step", CLType::U32),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grades_page",
+        vec![
+            Parameter::new("offset", CLType::U64),
+            Parameter::new("limit", CLType::U64),
+        ],
+        Vec::<TokenId>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grades_since",
+        vec![
+            Parameter::new("event_index", CLType::U64),
+            Parameter::new("limit", CLType::U64),
+        ],
+        Vec::<Meta>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "staff_events_since",
+        vec![
+            Parameter::new("event_index", CLType::U64),
+            Parameter::new("limit", CLType::U64),
+        ],
+        Vec::<Meta>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grade_record",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        Option::<GradeRecord>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grade",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("subject", CLType::String),
+            Parameter::new("year", CLType::U32),
+            Parameter::new("type", CLType::String),
+            Parameter::new("grade", CLType::U32),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "update_grade",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("grade", CLType::U32),
+            Parameter::new("token_id", TokenId::cl_type()),
+
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_grade_payload",
+        vec![
+            Parameter::new("token_id", TokenId::cl_type()),
+            Parameter::new("recipients", Vec::<PublicKey>::cl_type()),
+            Parameter::new("payload", Bytes::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grade_payload",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        Option::<GradePayload>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "link_pseudonym",
+        vec![
+            Parameter::new("pseudonym", Key::cl_type()),
+            Parameter::new("commitment", <[u8; 32]>::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "claim_pseudonym",
+        vec![
+            Parameter::new("pseudonym", Key::cl_type()),
+            Parameter::new("nonce", CLType::String),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pseudonym_account",
+        vec![Parameter::new("pseudonym", Key::cl_type())],
+        Option::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_guardian",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("guardian", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_guardian",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("guardian", Key::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "guardians",
+        vec![Parameter::new("student", Key::cl_type())],
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "acknowledge_grade",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "acknowledgement",
+        vec![
+            Parameter::new("token_id", TokenId::cl_type()),
+            Parameter::new("guardian", Key::cl_type()),
+        ],
+        Option::<u64>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "file_appeal",
+        vec![
+            Parameter::new("token_id", TokenId::cl_type()),
+            Parameter::new("reason", CLType::String),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "review_appeal",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "uphold_appeal",
+        vec![
+            Parameter::new("token_id", TokenId::cl_type()),
+            Parameter::new("grade", CLType::U32),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "reject_appeal",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "appeal",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        Option::<Appeal>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "revoke_grade",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_remove_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_revoke_grade",
+        vec![
+            Parameter::new("token_id", TokenId::cl_type()),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_approval_threshold",
+        vec![
+            Parameter::new("threshold", CLType::U32),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_add_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_appoint_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+            Parameter::new("start", CLType::U64),
+            Parameter::new("end", CLType::U64),
+            Parameter::new("subjects", Vec::<String>::cl_type()),
+            Parameter::new("classes", Vec::<String>::cl_type()),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_unpause",
+        vec![Parameter::new("deadline", CLType::U64)],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "pause",
+        vec![],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "approve_proposal",
+        vec![Parameter::new("proposal_id", CLType::U32)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "proposal",
+        vec![Parameter::new("proposal_id", CLType::U32)],
+        Option::<Proposal>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "appoint_teacher",
+        vec![
+            Parameter::new("teacher", Key::cl_type()),
+            Parameter::new("start", CLType::U64),
+            Parameter::new("end", CLType::U64),
+            Parameter::new("subjects", Vec::<String>::cl_type()),
+            Parameter::new("classes", Vec::<String>::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "appointment",
+        vec![Parameter::new("teacher", Key::cl_type())],
+        Option::<Appointment>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_student_class",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("class", CLType::String),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "student_class",
+        vec![Parameter::new("student", Key::cl_type())],
+        Option::<String>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "rollover_year",
+        vec![
+            Parameter::new("year", CLType::U32),
+            Parameter::new("archive", CLType::String),
+            Parameter::new("limit", CLType::U32),
+        ],
+        CLType::Bool,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_final_class_level",
+        vec![Parameter::new("level", CLType::U32)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "reopen_term",
+        vec![Parameter::new("year", CLType::U32)],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "propose_reopen_term",
+        vec![
+            Parameter::new("year", CLType::U32),
+            Parameter::new("deadline", CLType::U64),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "average",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("subject", CLType::String),
+            Parameter::new("year", CLType::U32),
+        ],
+        Option::<u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "final_average",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("subject", CLType::String),
+            Parameter::new("year", CLType::U32),
+        ],
+        Option::<u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "graduation",
+        vec![Parameter::new("student", Key::cl_type())],
+        Option::<u32>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "year_archive",
+        vec![Parameter::new("year", CLType::U32)],
+        Option::<YearArchive>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_reviewer",
+        vec![Parameter::new("reviewer", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_reviewer",
+        vec![Parameter::new("reviewer", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "issue_transfer",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("token_ids", Vec::<TokenId>::cl_type()),
+            Parameter::new("destination", Key::cl_type()),
+        ],
+        CLType::U32,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "transfer_record",
+        vec![Parameter::new("transfer_id", CLType::U32)],
+        Option::<Transfer>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "set_registry",
+        vec![Parameter::new("registry", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "import_transfer",
+        vec![
+            Parameter::new("school", Key::cl_type()),
+            Parameter::new("transfer_id", CLType::U32),
+            Parameter::new("student", Key::cl_type()),
+        ],
+        Vec::<TokenId>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "recognise_grade",
+        vec![
+            Parameter::new("student", Key::cl_type()),
+            Parameter::new("subject", CLType::String),
+            Parameter::new("year", CLType::U32),
+            Parameter::new("grade", CLType::U32),
+            Parameter::new("institution", CLType::String),
+            Parameter::new("original_scale", CLType::String),
+            Parameter::new("original_value", CLType::String),
+            Parameter::new("conversion_rule", CLType::String),
+        ],
+        Vec::<TokenId>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "grade_origin",
+        vec![Parameter::new("token_id", TokenId::cl_type())],
+        Option::<GradeOrigin>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+
+    entry_points
+}
+
+
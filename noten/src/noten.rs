@@ -3,182 +3,38 @@
 
 extern crate alloc;
 
-use alloc::collections::BTreeSet;
-use alloc::{format, vec};
+use alloc::format;
 use alloc::string::{String, ToString};
-use alloc::vec::Vec;
-use core::sync::atomic::AtomicU32;
 use casper_contract::contract_api::{runtime, storage};
 use casper_contract::unwrap_or_revert::UnwrapOrRevert;
-use casper_types::{ApiError, CLType, CLTyped, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPoints, EntryPointType, Group, Key, Parameter, runtime_args, RuntimeArgs, URef};
-use cep47::{CEP47, Error, Meta, TokenId};
-use cep47::contract_utils::{AdminControl, ContractContext, OnChainContractStorage};
+use casper_types::{ContractHash, ContractPackageHash, Key};
+use casper_types::contracts::{ContractVersion, NamedKeys};
+use cep47::Meta;
+use cep47::contract_utils::get_key;
+use noten::{get_entry_points, install_school, CONTRACT_VERSION_KEY};
+use noten_types::error::NotenError;
 
-#[derive(Default)]
-struct NotenContract(OnChainContractStorage);
-
-
-impl ContractContext<OnChainContractStorage> for NotenContract {
-    fn storage(&self) -> &OnChainContractStorage {
-        &self.0
-    }
-}
-
-impl CEP47<OnChainContractStorage> for NotenContract {}
-impl AdminControl<OnChainContractStorage> for NotenContract {}
-
-impl NotenContract {
-    fn constructor(&mut self, name: String, symbol: String, meta: Meta) {
-        CEP47::init(self, name, symbol, meta);
-        AdminControl::init(self);
-    }
-    fn grade(&mut self, student: Key, subject: String, year: u32, grade_type: String, grade: u32) -> Result<Vec<TokenId>, Error> {
-        //Check if the person who is calling this code is a teacher
-        let teacher = runtime::get_caller();
-        let admin = self.is_admin(Key::from(teacher));
-        if !admin {
-            runtime::revert(ApiError::User(20));
-        }
-        let mut meta = Meta::new();
-        meta.insert("subject".to_string(), subject);
-        meta.insert("year" .to_string(), year.to_string());
-        meta.insert("grade_type".to_string(), grade_type);
-        meta.insert("grade".to_string(), grade.to_string());
-        let mut list_of_meta = Vec::<Meta>::new();
-        list_of_meta.push(meta);
-
-        self.mint(student, Option::None, list_of_meta)
-
-
-    }
-    fn update_grade(&self, student: Key, grade: u32, token_id: TokenId) {}
-    fn remove_teacher(&mut self, teacher: Key) {
-        self.disable_admin(teacher)
-    }
-
-    fn add_teacher(&mut self, teacher: Key) {
-        self.add_admin(teacher)
-    }
-}
-
-#[no_mangle]
-fn constructor() {
-    let name = runtime::get_named_arg::<String>("name");
-    let symbol = runtime::get_named_arg::<String>("symbol");
-    let meta = runtime::get_named_arg::<Meta>("meta");
-    NotenContract::default().constructor(name, symbol, meta);
-    NotenContract::default().add_admin_without_checked(Key::Account(runtime::get_caller()));
-}
-
-#[no_mangle]
-fn name() {
-    let ret = NotenContract::default().name();
-    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
-}
-
-#[no_mangle]
-fn symbol() {
-    let ret = NotenContract::default().symbol();
-    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
-}
-
-#[no_mangle]
-fn meta() {
-    let ret = NotenContract::default().meta();
-    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
-}
-
-/*
-teachers can give grades to students
- */
-#[no_mangle]
-pub extern "C" fn grade() {
-    let student:Key  = runtime::get_named_arg("student");
-    let subject:String = runtime::get_named_arg("subject");
-    let year:u32 = runtime::get_named_arg("year");
-    let grade_type:String = runtime::get_named_arg("type");
-    let grade:u32 = runtime::get_named_arg("grade");
-    NotenContract::default().grade(student, subject, year, grade_type, grade);
-}
 /*
-teachers can update/change the grade
+sets up the smart contract, or adds a new version if this account already installed it
  */
 #[no_mangle]
-pub extern "C" fn update_grade() {
-    let student: Key = runtime::get_named_arg("student");
-    let grade: u32 = runtime::get_named_arg("grade");
-    let token_id:TokenId = runtime::get_named_arg("token_id");
-    NotenContract::default().update_grade(student, grade, token_id);
-    // Parameter::new("student", Key::cl_type()),
-    // Parameter::new("grade", CLType::U32),
-    // Parameter::new("token_id", TokenId::cl_type()),
-}
-/*
-teachers can be removed, so they cant issue grades anymore
- */
-#[no_mangle]
-pub extern "C" fn remove_teacher() {
-    let teacher: Key = runtime::get_named_arg("teacher");
-    NotenContract::default().remove_teacher(teacher);
-}
-/*
-teachers can be added, so they can issue grades
- */
-#[no_mangle]
-pub extern "C" fn add_teacher() {
-    let teacher: Key = runtime::get_named_arg("teacher");
-    NotenContract::default().add_teacher(teacher);
+pub extern "C" fn call() {
+    let contract_name: String = runtime::get_named_arg("contract_name");
+    match get_key::<ContractPackageHash>(&format!("{}_package_hash_wrapped", contract_name)) {
+        Some(package_hash) => upgrade(contract_name, package_hash),
+        None => install(contract_name),
+    }
 }
 
-#[no_mangle]
-pub extern "C" fn token_meta(){
-    let token_id: TokenId = runtime::get_named_arg("token_id");
-    NotenContract::default().token_meta(token_id);
-}
-/*
-sets up the smart contract
- */
-#[no_mangle]
-pub extern "C" fn call() {
-    // Read arguments for the constructor call.
+fn install(contract_name: String) {
     let name: String = runtime::get_named_arg("name");
     let symbol: String = runtime::get_named_arg("symbol");
     let meta: Meta = runtime::get_named_arg("meta");
-    let contract_name: String = runtime::get_named_arg("contract_name");
-
-    // Prepare constructor args
-    let constructor_args:RuntimeArgs = runtime_args! {
-        "name" => name,
-        "symbol" => symbol,
-        "meta" => meta
-    };
-
-    let (contract_hash, _) = storage::new_contract(
-        get_entry_points(),
-        None,
-        Some(String::from("contract_package_hash")),
-        None,
-    );
+    let admin = Key::Account(runtime::get_caller());
 
-    let package_hash: ContractPackageHash = ContractPackageHash::new(
-        runtime::get_key("contract_package_hash")
-            .unwrap_or_revert()
-            .into_hash()
-            .unwrap_or_revert(),
-    );
-
-    let constructor_access: URef =
-        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
-            .unwrap_or_revert()
-            .pop()
-            .unwrap_or_revert();
-
-    let _: () = runtime::call_contract(contract_hash, "constructor", constructor_args);
-
-    let mut urefs = BTreeSet::new();
-    urefs.insert(constructor_access);
-    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
-        .unwrap_or_revert();
+    let (package_hash, access_uref, contract_hash, version_uref) = install_school(name, symbol, meta, admin);
+    runtime::put_key(&format!("{}_access_uref", contract_name), access_uref.into());
+    runtime::put_key(&format!("{}_contract_version", contract_name), version_uref.into());
 
     runtime::put_key(
         format!("{}_contract_hash", contract_name).as_str(),
@@ -194,99 +50,43 @@ pub extern "C" fn call() {
     );
 }
 
-fn get_entry_points() -> EntryPoints {
-    let mut entry_points = EntryPoints::new();
-    entry_points.add_entry_point(EntryPoint::new(
-        "constructor",
-        vec![
-            Parameter::new("name", String::cl_type()),
-            Parameter::new("symbol", String::cl_type()),
-            Parameter::new("meta", Meta::cl_type()),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Groups(vec![Group::new("constructor")]),
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "name",
-        vec![],
-        String::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "symbol",
-        vec![],
-        String::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-    entry_points.add_entry_point(EntryPoint::new(
-        "meta",
-        vec![],
-        Meta::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "token_meta",
-        vec![Parameter::new("token_id", TokenId::cl_type())],
-        Meta::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "grade",
-        vec![
-            Parameter::new("student", Key::cl_type()),
-            Parameter::new("subject", CLType::String),
-            Parameter::new("year", CLType::U32),
-            Parameter::new("type", CLType::String),
-            Parameter::new("grade", CLType::U32),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "update_grade",
-        vec![
-            Parameter::new("student", Key::cl_type()),
-            Parameter::new("grade", CLType::U32),
-            Parameter::new("token_id", TokenId::cl_type()),
-
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "add_teacher",
-        vec![
-            Parameter::new("teacher", Key::cl_type()),
-
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
-
-    entry_points.add_entry_point(EntryPoint::new(
-        "remove_teacher",
-        vec![
-            Parameter::new("teacher", Key::cl_type()),
-        ],
-        <()>::cl_type(),
-        EntryPointAccess::Public,
-        EntryPointType::Contract,
-    ));
+/// Needs the package's access uref, which packages installed before upgrades were supported don't have:
+/// those are installed anew under another contract name and their grades brought over with
+/// `import_legacy_grade`. The previous version's named keys carry over, the `migrate` entry point
+/// takes care of the rest.
+fn upgrade(contract_name: String, package_hash: ContractPackageHash) {
+    if runtime::get_key(&format!("{}_access_uref", contract_name)).is_none() {
+        runtime::revert(NotenError::LegacyPackage);
+    }
+    let previous_contract_hash = ContractHash::new(
+        runtime::get_key(format!("{}_contract_hash", contract_name).as_str())
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
 
+    // Packages installed before the version was kept get the named key now.
+    let mut named_keys = NamedKeys::new();
+    let version_uref = match runtime::get_key(&format!("{}_contract_version", contract_name)) {
+        Some(key) => key.into_uref().unwrap_or_revert(),
+        None => {
+            let uref = storage::new_uref(ContractVersion::default());
+            named_keys.insert(CONTRACT_VERSION_KEY.to_string(), uref.into());
+            runtime::put_key(&format!("{}_contract_version", contract_name), uref.into());
+            uref
+        }
+    };
+    let (contract_hash, contract_version) = storage::add_contract_version(package_hash, get_entry_points(), named_keys);
+    storage::write(version_uref, contract_version);
+    storage::disable_contract_version(package_hash, previous_contract_hash).unwrap_or_revert();
 
-    entry_points
+    runtime::put_key(
+        format!("{}_contract_hash", contract_name).as_str(),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        format!("{}_contract_hash_wrapped", contract_name).as_str(),
+        storage::new_uref(contract_hash).into(),
+    );
 }
 
-
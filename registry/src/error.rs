@@ -0,0 +1,23 @@
+use casper_types::ApiError;
+
+/// Errors the registry contract reverts with, reported as `ApiError::User` codes.
+#[repr(u16)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RegistryError {
+    /// The caller is not a canton administrator (same code `AdminControl` uses).
+    NotCantonAdmin = 20,
+    /// Schools are registered by their contract package hash, a `Key::Hash`.
+    InvalidSchool = 21,
+    /// The school's `meta` names a different canton.
+    WrongKanton = 22,
+    /// The school is already registered.
+    AlreadyRegistered = 23,
+    /// The school isn't registered.
+    UnknownSchool = 24,
+}
+
+impl From<RegistryError> for ApiError {
+    fn from(error: RegistryError) -> ApiError {
+        ApiError::User(error as u16)
+    }
+}
@@ -0,0 +1,365 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+mod error;
+
+use alloc::collections::BTreeSet;
+use alloc::{format, vec};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use casper_contract::contract_api::{runtime, storage};
+use casper_contract::unwrap_or_revert::UnwrapOrRevert;
+use casper_types::{CLType, CLTyped, CLValue, ContractPackageHash, EntryPoint, EntryPointAccess, EntryPoints, EntryPointType, Group, Key, Parameter, runtime_args, RuntimeArgs, URef};
+use cep47::Meta;
+use cep47::contract_utils::{AdminControl, ContractContext, Dict, get_key, key_to_str, OnChainContractStorage, set_key};
+use noten_types::page::page;
+use crate::error::RegistryError;
+
+const KANTON_KEY: &str = "kanton";
+const META_KEY: &str = "meta";
+const SCHOOL_COUNT_KEY: &str = "school_count";
+const SCHOOLS_DICT: &str = "schools";
+const SCHOOL_ENTRIES_DICT: &str = "school_entries";
+
+/// A registered school: its name, the `meta` it reported when registering and whether it's still active.
+type School = (String, Meta, bool);
+
+/// Canton-level registry of the schools' noten contracts.
+///
+/// A contract can only install code from its own wasm, so the registry is built with the noten
+/// contract's code and exports its entry points: `create_school` installs a school from it. Schools
+/// installed by their own `noten.wasm` deploy are registered by the canton office afterwards.
+#[derive(Default)]
+struct RegistryContract(OnChainContractStorage);
+
+impl ContractContext<OnChainContractStorage> for RegistryContract {
+    fn storage(&self) -> &OnChainContractStorage {
+        &self.0
+    }
+}
+
+impl AdminControl<OnChainContractStorage> for RegistryContract {}
+
+impl RegistryContract {
+    fn init(&mut self, kanton: String, meta: Meta) {
+        AdminControl::init(self);
+        Dict::init(SCHOOLS_DICT);
+        Dict::init(SCHOOL_ENTRIES_DICT);
+        set_key(KANTON_KEY, kanton);
+        set_key(META_KEY, meta);
+        set_key(SCHOOL_COUNT_KEY, 0u64);
+    }
+    fn assert_canton_admin(&self) {
+        if !self.is_admin(self.get_caller()) {
+            runtime::revert(RegistryError::NotCantonAdmin);
+        }
+    }
+    /// Registers a school's contract package after checking its `meta` belongs to this canton.
+    fn register_school(&mut self, school: Key, name: String) {
+        self.assert_canton_admin();
+        let package_hash = ContractPackageHash::new(school.into_hash().unwrap_or_revert_with(RegistryError::InvalidSchool));
+        if self.school(school).is_some() {
+            runtime::revert(RegistryError::AlreadyRegistered);
+        }
+        let meta: Meta = runtime::call_versioned_contract(package_hash, None, "meta", runtime_args! {});
+        self.add_school(school, name, meta);
+    }
+    /// Installs a new school of this canton with `admin` as its first teacher, and registers it.
+    /// The registry keeps the package's access uref, as `{school}_access_uref`, for upgrades.
+    fn create_school(&mut self, name: String, symbol: String, meta: Meta, admin: Key) -> Key {
+        self.assert_canton_admin();
+        self.assert_kanton(&meta);
+        let (package_hash, access_uref, _, version_uref) = noten::install_school(name.clone(), symbol, meta.clone(), admin);
+        let school = Key::Hash(package_hash.value());
+        runtime::put_key(&format!("{}_access_uref", key_to_str(&school)), access_uref.into());
+        runtime::put_key(&format!("{}_contract_version", key_to_str(&school)), version_uref.into());
+        self.add_school(school, name, meta);
+        school
+    }
+    fn assert_kanton(&self, meta: &Meta) {
+        if meta.get(KANTON_KEY) != Some(&self.kanton()) {
+            runtime::revert(RegistryError::WrongKanton);
+        }
+    }
+    fn add_school(&mut self, school: Key, name: String, meta: Meta) {
+        self.assert_kanton(&meta);
+        let count = self.school_count();
+        Dict::instance(SCHOOLS_DICT).set(&count.to_string(), school);
+        let entry: School = (name, meta, true);
+        Dict::instance(SCHOOL_ENTRIES_DICT).set(&key_to_str(&school), entry);
+        set_key(SCHOOL_COUNT_KEY, count + 1);
+    }
+    /// Closed schools stay listed, so their grades can still be looked up.
+    fn deregister_school(&mut self, school: Key) {
+        self.assert_canton_admin();
+        let (name, meta, _) = self.school(school).unwrap_or_revert_with(RegistryError::UnknownSchool);
+        let entry: School = (name, meta, false);
+        Dict::instance(SCHOOL_ENTRIES_DICT).set(&key_to_str(&school), entry);
+    }
+    fn school(&self, school: Key) -> Option<School> {
+        Dict::instance(SCHOOL_ENTRIES_DICT).get(&key_to_str(&school))
+    }
+    fn school_count(&self) -> u64 {
+        get_key(SCHOOL_COUNT_KEY).unwrap_or_default()
+    }
+    /// The schools at `offset` to `offset + limit`, at most `MAX_PAGE_SIZE`.
+    fn schools(&self, offset: u64, limit: u64) -> Vec<Key> {
+        page(offset, limit, self.school_count())
+            .map(|index| Dict::instance(SCHOOLS_DICT).get(&index.to_string()).unwrap_or_revert())
+            .collect()
+    }
+    fn kanton(&self) -> String {
+        get_key(KANTON_KEY).unwrap_or_revert()
+    }
+    fn add_canton_admin(&mut self, admin: Key) {
+        self.add_admin(admin)
+    }
+    fn remove_canton_admin(&mut self, admin: Key) {
+        self.disable_admin(admin)
+    }
+}
+
+/*
+`constructor` and `meta` are the noten contract's, which the registry is built with: schools need
+them, and noten's `meta` returns the registry's `meta` named key just the same
+ */
+#[no_mangle]
+fn init() {
+    let kanton = runtime::get_named_arg::<String>("kanton");
+    let meta = runtime::get_named_arg::<Meta>("meta");
+    RegistryContract::default().init(kanton, meta);
+    RegistryContract::default().add_admin_without_checked(Key::Account(runtime::get_caller()));
+}
+
+#[no_mangle]
+fn kanton() {
+    let ret = RegistryContract::default().kanton();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+the canton office registers the contract of a school once it is installed
+ */
+#[no_mangle]
+pub extern "C" fn register_school() {
+    let school: Key = runtime::get_named_arg("school");
+    let name: String = runtime::get_named_arg("name");
+    RegistryContract::default().register_school(school, name);
+}
+
+/*
+or has the registry install a new school from the noten code it is built with
+ */
+#[no_mangle]
+pub extern "C" fn create_school() {
+    let name: String = runtime::get_named_arg("name");
+    let symbol: String = runtime::get_named_arg("symbol");
+    let meta: Meta = runtime::get_named_arg("meta");
+    let admin: Key = runtime::get_named_arg("admin");
+    let ret = RegistryContract::default().create_school(name, symbol, meta, admin);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn deregister_school() {
+    let school: Key = runtime::get_named_arg("school");
+    RegistryContract::default().deregister_school(school);
+}
+
+#[no_mangle]
+pub extern "C" fn school() {
+    let school: Key = runtime::get_named_arg("school");
+    let ret = RegistryContract::default().school(school);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn school_count() {
+    let ret = RegistryContract::default().school_count();
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+#[no_mangle]
+pub extern "C" fn schools() {
+    let offset: u64 = runtime::get_named_arg("offset");
+    let limit: u64 = runtime::get_named_arg("limit");
+    let ret = RegistryContract::default().schools(offset, limit);
+    runtime::ret(CLValue::from_t(ret).unwrap_or_revert());
+}
+
+/*
+canton administrators can be added and removed by other canton administrators
+ */
+#[no_mangle]
+pub extern "C" fn add_canton_admin() {
+    let admin: Key = runtime::get_named_arg("admin");
+    RegistryContract::default().add_canton_admin(admin);
+}
+
+#[no_mangle]
+pub extern "C" fn remove_canton_admin() {
+    let admin: Key = runtime::get_named_arg("admin");
+    RegistryContract::default().remove_canton_admin(admin);
+}
+
+/*
+sets up the registry contract
+ */
+#[no_mangle]
+pub extern "C" fn call() {
+    let kanton: String = runtime::get_named_arg("kanton");
+    let meta: Meta = runtime::get_named_arg("meta");
+    let contract_name: String = runtime::get_named_arg("contract_name");
+
+    let constructor_args: RuntimeArgs = runtime_args! {
+        "kanton" => kanton,
+        "meta" => meta
+    };
+
+    let (contract_hash, _) = storage::new_contract(
+        get_entry_points(),
+        None,
+        Some(String::from("contract_package_hash")),
+        Some(format!("{}_access_uref", contract_name)),
+    );
+
+    let package_hash: ContractPackageHash = ContractPackageHash::new(
+        runtime::get_key("contract_package_hash")
+            .unwrap_or_revert()
+            .into_hash()
+            .unwrap_or_revert(),
+    );
+
+    let constructor_access: URef =
+        storage::create_contract_user_group(package_hash, "constructor", 1, Default::default())
+            .unwrap_or_revert()
+            .pop()
+            .unwrap_or_revert();
+
+    let _: () = runtime::call_contract(contract_hash, "init", constructor_args);
+
+    let mut urefs = BTreeSet::new();
+    urefs.insert(constructor_access);
+    storage::remove_contract_user_group_urefs(package_hash, "constructor", urefs)
+        .unwrap_or_revert();
+
+    runtime::put_key(
+        format!("{}_contract_hash", contract_name).as_str(),
+        contract_hash.into(),
+    );
+    runtime::put_key(
+        format!("{}_contract_hash_wrapped", contract_name).as_str(),
+        storage::new_uref(contract_hash).into(),
+    );
+    runtime::put_key(
+        format!("{}_package_hash_wrapped", contract_name).as_str(),
+        storage::new_uref(package_hash).into(),
+    );
+}
+
+fn get_entry_points() -> EntryPoints {
+    let mut entry_points = EntryPoints::new();
+    entry_points.add_entry_point(EntryPoint::new(
+        "init",
+        vec![
+            Parameter::new("kanton", String::cl_type()),
+            Parameter::new("meta", Meta::cl_type()),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Groups(vec![Group::new("constructor")]),
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "kanton",
+        vec![],
+        String::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+    entry_points.add_entry_point(EntryPoint::new(
+        "meta",
+        vec![],
+        Meta::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "register_school",
+        vec![
+            Parameter::new("school", Key::cl_type()),
+            Parameter::new("name", CLType::String),
+        ],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "create_school",
+        vec![
+            Parameter::new("name", CLType::String),
+            Parameter::new("symbol", CLType::String),
+            Parameter::new("meta", Meta::cl_type()),
+            Parameter::new("admin", Key::cl_type()),
+        ],
+        Key::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "deregister_school",
+        vec![Parameter::new("school", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "school",
+        vec![Parameter::new("school", Key::cl_type())],
+        Option::<School>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "school_count",
+        vec![],
+        CLType::U64,
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "schools",
+        vec![
+            Parameter::new("offset", CLType::U64),
+            Parameter::new("limit", CLType::U64),
+        ],
+        Vec::<Key>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "add_canton_admin",
+        vec![Parameter::new("admin", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points.add_entry_point(EntryPoint::new(
+        "remove_canton_admin",
+        vec![Parameter::new("admin", Key::cl_type())],
+        <()>::cl_type(),
+        EntryPointAccess::Public,
+        EntryPointType::Contract,
+    ));
+
+    entry_points
+}
@@ -1,15 +1,83 @@
-use std::{collections::BTreeMap, path::PathBuf};
-use blake2::digest::VariableOutput;
-use blake2::VarBlake2b;
-use blake2::digest::Update;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
 
 use casper_engine_test_support::{DEFAULT_ACCOUNT_ADDR, DEFAULT_RUN_GENESIS_REQUEST, ARG_AMOUNT, DEFAULT_PAYMENT, DeployItemBuilder, ExecuteRequestBuilder, InMemoryWasmTestBuilder};
-use casper_execution_engine::core::engine_state::ExecuteRequest;
-use casper_types::{account::AccountHash, ContractHash, ContractPackageHash, Key, runtime_args, RuntimeArgs, U512, U256, CLTyped, SecretKey, PublicKey, StoredValue, system::mint};
-use casper_types::bytesrepr::{FromBytes, ToBytes};
-use cep47::TokenId;
+use casper_execution_engine::core::engine_state::{self, ExecuteRequest};
+use casper_execution_engine::core::execution;
+use casper_types::{account::AccountHash, ContractHash, ContractPackageHash, Key, runtime_args, RuntimeArgs, U512, U256, CLTyped, CLValue, SecretKey, PublicKey, StoredValue, system::mint};
+use casper_types::bytesrepr::{Bytes, FromBytes};
+use cep47::{Meta, TokenId};
 use maplit::btreemap;
+use noten_client::{call, decode, keys, NotenCall, NotenError};
+use noten_client::appointment::Appointment;
+use noten_client::exam::Exam;
+use noten_client::origin::Recognition;
+use noten_client::record::GradeRecord;
+use noten_client::scale::GradingScale;
+use noten_client::StateReader;
 use rand::Rng;
+use sha2::{Digest, Sha256};
+
+/// The path of a contract's wasm in `tests/wasm`, where `make test` copies it after building it,
+/// together with the hash of the sources it was built from. Panics if the wasm is missing or the
+/// sources have changed since, so the tests don't run an outdated contract. Modification times
+/// can't tell, as git doesn't keep them.
+pub fn wasm_path(file: &Path) -> PathBuf {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let wasm = root.join("tests").join("wasm").join(file);
+    let built_from = fs::read_to_string(wasm.with_extension("sources"))
+        .ok()
+        .filter(|_| wasm.exists())
+        .unwrap_or_else(|| panic!("{} is missing, make test builds it", wasm.display()));
+    let contract = file.file_stem().and_then(|stem| stem.to_str()).expect("a wasm file name");
+    // The crates the contract is built from, as `make copy-wasm-file-to-test` lists them.
+    let crates = match contract {
+        "noten" => vec![contract, "noten-types"],
+        "registry" => vec![contract, "noten", "noten-types"],
+        _ => vec![contract],
+    };
+    assert_eq!(
+        built_from.trim(),
+        sources_hash(&root, &crates),
+        "{} was built from other sources than those in {}, make test rebuilds it",
+        wasm.display(),
+        crates.join(", ")
+    );
+    wasm
+}
+
+/// What `make copy-wasm-file-to-test` records: the sha256 of `sha256sum`'s lines for the files of
+/// the crates' `src` and their `Cargo.toml`, sorted by their path from the workspace root.
+fn sources_hash(root: &Path, crates: &[&str]) -> String {
+    let mut files = Vec::new();
+    for name in crates {
+        files.push(Path::new(name).join("Cargo.toml"));
+        source_files(root, &Path::new(name).join("src"), &mut files);
+    }
+    let mut files: Vec<String> = files.iter().map(|file| file.to_string_lossy().into_owned()).collect();
+    files.sort();
+    let lines: String = files
+        .iter()
+        .map(|file| {
+            let content = fs::read(root.join(file)).unwrap_or_else(|error| panic!("{}: {}", file, error));
+            format!("{}  {}\n", hex::encode(Sha256::digest(&content)), file)
+        })
+        .collect();
+    hex::encode(Sha256::digest(lines.as_bytes()))
+}
+
+/// The files in `dir`, relative to `root`, and in its subdirectories.
+fn source_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(root.join(dir)).unwrap_or_else(|error| panic!("{}: {}", dir.display(), error)) {
+        let path = dir.join(entry.unwrap().file_name());
+        if root.join(&path).is_dir() {
+            source_files(root, &path, files);
+        } else {
+            files.push(path);
+        }
+    }
+}
 
 pub enum DeploySource {
     Code(PathBuf),
@@ -27,6 +95,7 @@ pub struct NotenContract {
     pub builder: InMemoryWasmTestBuilder,
     pub noten: (ContractHash, ContractPackageHash),
     pub accounts: (AccountHash, AccountHash, AccountHash, AccountHash, AccountHash, AccountHash),
+    pub secret_keys: BTreeMap<AccountHash, SecretKey>,
 }
 
 impl NotenContract {
@@ -37,11 +106,24 @@ impl NotenContract {
     }
 
     pub fn create_account() -> AccountHash {
-        let key = SecretKey::ed25519_from_bytes(rand::thread_rng().gen::<[u8; 32]>()).unwrap();
+        let key = Self::create_secret_key();
         let pk = PublicKey::from(&key);
         pk.to_account_hash()
     }
 
+    pub fn create_secret_key() -> SecretKey {
+        SecretKey::ed25519_from_bytes(rand::thread_rng().gen::<[u8; 32]>()).unwrap()
+    }
+
+    /// Creates and funds another account, whose key `public_key` knows.
+    pub fn add_account(&mut self) -> AccountHash {
+        let key = Self::create_secret_key();
+        let account = PublicKey::from(&key).to_account_hash();
+        self.builder.exec(Self::fund_account(&account, U512::from(50_000_000_000_000_u64))).expect_success().commit();
+        self.secret_keys.insert(account, key);
+        account
+    }
+
     pub fn fund_account(account: &AccountHash, amount: U512) -> ExecuteRequest {
         let deploy_item = DeployItemBuilder::new()
             .with_address(*DEFAULT_ACCOUNT_ADDR)
@@ -59,12 +141,19 @@ impl NotenContract {
     }
 
     pub fn deploy_noten() -> Self {
-        let admin = Self::create_account();
-        let biff = Self::create_account();
-        let tim = Self::create_account();
-        let ali = Self::create_account();
-        let bob = Self::create_account();
-        let dan = Self::create_account();
+        let mut secret_keys = BTreeMap::new();
+        let mut create_account = || {
+            let key = Self::create_secret_key();
+            let account = PublicKey::from(&key).to_account_hash();
+            secret_keys.insert(account, key);
+            account
+        };
+        let admin = create_account();
+        let biff = create_account();
+        let tim = create_account();
+        let ali = create_account();
+        let bob = create_account();
+        let dan = create_account();
 
         let mut builder = InMemoryWasmTestBuilder::default();
         let base_amount = U512::from(50_000_000_000_000_u64);
@@ -83,6 +172,7 @@ impl NotenContract {
             builder,
             noten: (nft_hash, nft_package),
             accounts: (admin, biff, tim, ali, bob, dan),
+            secret_keys,
         }
     }
 
@@ -90,15 +180,11 @@ impl NotenContract {
         builder: &mut InMemoryWasmTestBuilder,
         admin: &AccountHash,
     ) -> (ContractHash, ContractPackageHash) {
-        let token_args = runtime_args! {
-            "name" => "noten",
-            "symbol" => "NOT",
-            "meta" => btreemap! {
-                "school".to_string() => "Zuger Kantonal".to_string(),
-                "kanton".to_string() => "ZG".to_string()
-            },
-            "contract_name" => "noten".to_string()
+        let meta = btreemap! {
+            "school".to_string() => "Zuger Kantonal".to_string(),
+            "kanton".to_string() => "ZG".to_string()
         };
+        let token_args = call::install_args("noten", "NOT", meta, "noten");
         let nft_code = PathBuf::from("noten.wasm");
         Self::deploy(
             builder,
@@ -123,6 +209,153 @@ impl NotenContract {
     }
 
 
+    /// Deploys the contract code again, which adds a new version to the installed package.
+    pub fn upgrade_noten(&mut self) {
+        let admin = self.accounts.0;
+        Self::deploy(
+            &mut self.builder,
+            &admin,
+            &DeploySource::Code(PathBuf::from("noten.wasm")),
+            call::upgrade_args("noten"),
+            true,
+            None,
+        );
+        self.noten.0 = Self::query(
+            &self.builder,
+            Key::Account(admin),
+            &["noten_contract_hash_wrapped".to_string()],
+        );
+    }
+
+    pub fn migrate(&mut self, caller: &AccountHash, token_ids: Vec<TokenId>) {
+        self.call(caller, NotenCall::migrate(token_ids), Self::get_now_u64())
+    }
+
+    pub fn issue_transfer<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, token_ids: Vec<TokenId>, destination: ContractPackageHash) {
+        self.call(caller, NotenCall::issue_transfer(student.into(), token_ids, Key::Hash(destination.value())), Self::get_now_u64())
+    }
+
+    pub fn import_transfer<T: Into<Key>>(&mut self, caller: &AccountHash, school: ContractPackageHash, transfer_id: u32, student: T) {
+        self.call(caller, NotenCall::import_transfer(Key::Hash(school.value()), transfer_id, student.into()), Self::get_now_u64())
+    }
+
+    pub fn set_grading_scale(&mut self, caller: &AccountHash, min: u32, max: u32, step: u32) {
+        self.call(caller, NotenCall::set_grading_scale(GradingScale { min, max, step }), Self::get_now_u64())
+    }
+
+    pub fn register_exam(&mut self, caller: &AccountHash, subject: &str, class: &str, year: u32, exam_type: &str) {
+        self.call(caller, NotenCall::register_exam(&Exam {
+            subject: subject.to_string(),
+            class: class.to_string(),
+            year,
+            date: Self::get_now_u64(),
+            exam_type: exam_type.to_string(),
+        }), Self::get_now_u64())
+    }
+
+    pub fn grade_exam<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, exam_id: u32, grade: u32) {
+        self.call(caller, NotenCall::grade_exam(student.into(), exam_id, grade), Self::get_now_u64())
+    }
+
+    /// `recognition` is the institution, the original scale and value, and the conversion rule.
+    pub fn recognise_grade<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, subject: &str, year: u32, grade: u32, recognition: (&str, &str, &str, &str)) {
+        let (institution, scale, value, rule) = recognition;
+        let recognition = Recognition {
+            institution: institution.to_string(),
+            scale: scale.to_string(),
+            value: value.to_string(),
+            rule: rule.to_string(),
+        };
+        self.call(caller, NotenCall::recognise_grade(student.into(), subject, year, grade, &recognition), Self::get_now_u64())
+    }
+
+    /// Installs the canton registry with `deployer` as its first canton administrator.
+    pub fn deploy_registry(&mut self, deployer: &AccountHash, kanton: &str) -> (ContractHash, ContractPackageHash) {
+        Self::deploy(
+            &mut self.builder,
+            deployer,
+            &DeploySource::Code(PathBuf::from("registry.wasm")),
+            runtime_args! {
+                "kanton" => kanton.to_string(),
+                "meta" => btreemap! {
+                    "name".to_string() => format!("Kanton {}", kanton)
+                },
+                "contract_name" => "registry".to_string()
+            },
+            true,
+            None,
+        );
+        let contract_hash: ContractHash = Self::query(
+            &self.builder,
+            Key::Account(*deployer),
+            &["registry_contract_hash_wrapped".to_string()],
+        );
+        let contract_package: ContractPackageHash = Self::query(
+            &self.builder,
+            Key::Account(*deployer),
+            &["registry_package_hash_wrapped".to_string()],
+        );
+        (contract_hash, contract_package)
+    }
+
+    pub fn register_school(&mut self, caller: &AccountHash, registry: ContractPackageHash, name: &str) {
+        Self::deploy(
+            &mut self.builder,
+            caller,
+            &DeploySource::ByPackageHash {
+                package_hash: registry,
+                method: "register_school".to_string(),
+            },
+            runtime_args! {
+                "school" => Key::Hash(self.noten.1.value()),
+                "name" => name.to_string(),
+            },
+            true,
+            Some(Self::get_now_u64()),
+        );
+    }
+
+    /// Has the registry install a school of `kanton` with `admin` as its first teacher.
+    pub fn create_school(&mut self, caller: &AccountHash, registry: ContractPackageHash, name: &str, kanton: &str, admin: AccountHash) {
+        Self::deploy(
+            &mut self.builder,
+            caller,
+            &DeploySource::ByPackageHash {
+                package_hash: registry,
+                method: "create_school".to_string(),
+            },
+            runtime_args! {
+                "name" => name.to_string(),
+                "symbol" => "NOT".to_string(),
+                "meta" => btreemap! {
+                    "school".to_string() => name.to_string(),
+                    "kanton".to_string() => kanton.to_string()
+                },
+                "admin" => Key::Account(admin),
+            },
+            true,
+            Some(Self::get_now_u64()),
+        );
+    }
+
+    /// Registers the current school with a new registry of canton ZG deployed by `canton_admin`,
+    /// so schools using that registry accept its transfers.
+    pub fn register_for_transfers(&mut self, canton_admin: &AccountHash) -> ContractPackageHash {
+        let (_, registry) = self.deploy_registry(canton_admin, "ZG");
+        self.register_school(canton_admin, registry, "Kantonsschule Zug");
+        registry
+    }
+
+    pub fn set_registry(&mut self, caller: &AccountHash, registry: ContractPackageHash) {
+        self.call(caller, NotenCall::set_registry(Key::Hash(registry.value())), Self::get_now_u64())
+    }
+
+    pub fn registered_school(&self, registry: &ContractHash, index: u64) -> Option<(Key, String, Meta, bool)> {
+        let school: Key = self.query_dictionary(registry, "schools", index.to_string())?;
+        let (name, meta, active) = self.query_dictionary(registry, "school_entries", keys::key_to_str(&school))?;
+        Some((school, name, meta, active))
+    }
+
     pub fn mint_nft<T: Into<Key>>(
         &mut self,
         recipient: T,
@@ -153,23 +386,88 @@ impl NotenContract {
     }
 
     pub fn add_teacher<T: Into<Key>>(&mut self, caller: &AccountHash, teacher: T) {
-        self.call(caller, "add_teacher", runtime_args! {
-            "teacher" => teacher.into(),
-        }, Self::get_now_u64())
+        self.call(caller, NotenCall::add_teacher(teacher.into()), Self::get_now_u64())
     }
     pub fn remove_teacher<T: Into<Key>>(&mut self, caller: &AccountHash, teacher: T) {
-        self.call(caller, "remove_teacher", runtime_args! {
-            "teacher" => teacher.into(),
-        }, Self::get_now_u64())
+        self.call(caller, NotenCall::remove_teacher(teacher.into()), Self::get_now_u64())
+    }
+    pub fn appoint_teacher<T: Into<Key>>(&mut self, caller: &AccountHash, teacher: T, start: u64, end: u64, subjects: Vec<String>, classes: Vec<String>) {
+        self.call(caller, NotenCall::appoint_teacher(teacher.into(), &Appointment { start, end, subjects, classes }), Self::get_now_u64())
+    }
+    pub fn set_student_class<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, class: String) {
+        self.call(caller, NotenCall::set_student_class(student.into(), &class), Self::get_now_u64())
+    }
+    pub fn grade_at<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, subject: String, year: u32, grade_type: String, grade: u32, time: u64) {
+        self.call(caller, NotenCall::grade(student.into(), &subject, year, &grade_type, grade), time)
     }
     pub fn grade<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, subject: String, year: u32, grade_type: String, grade: u32) {
-        self.call(caller, "grade", runtime_args! {
-            "student" => student.into(),
-            "subject"=> subject,
-            "year" => year,
-            "type" => grade_type,
-            "grade" => grade,
-        }, Self::get_now_u64())
+        self.call(caller, NotenCall::grade(student.into(), &subject, year, &grade_type, grade), Self::get_now_u64())
+    }
+
+    pub fn set_grade_payload(&mut self, caller: &AccountHash, token_id: TokenId, recipients: Vec<PublicKey>, payload: Bytes) {
+        self.call(caller, NotenCall::set_grade_payload(token_id, recipients, payload), Self::get_now_u64())
+    }
+
+    pub fn link_pseudonym(&mut self, caller: &AccountHash, pseudonym: Key, commitment: [u8; 32]) {
+        self.call(caller, NotenCall::link_pseudonym(pseudonym, commitment), Self::get_now_u64())
+    }
+    pub fn claim_pseudonym(&mut self, caller: &AccountHash, pseudonym: Key, nonce: String) {
+        self.call(caller, NotenCall::claim_pseudonym(pseudonym, &nonce), Self::get_now_u64())
+    }
+
+    pub fn add_guardian<T: Into<Key>, G: Into<Key>>(&mut self, caller: &AccountHash, student: T, guardian: G) {
+        self.call(caller, NotenCall::add_guardian(student.into(), guardian.into()), Self::get_now_u64())
+    }
+    pub fn acknowledge_grade(&mut self, caller: &AccountHash, token_id: TokenId) {
+        self.call(caller, NotenCall::acknowledge_grade(token_id), Self::get_now_u64())
+    }
+
+    pub fn public_key(&self, account: &AccountHash) -> PublicKey {
+        PublicKey::from(&self.secret_keys[account])
+    }
+
+    pub fn update_grade<T: Into<Key>>(&mut self, caller: &AccountHash, student: T, grade: u32, token_id: TokenId) {
+        self.call(caller, NotenCall::update_grade(student.into(), grade, token_id), Self::get_now_u64())
+    }
+    pub fn file_appeal(&mut self, caller: &AccountHash, token_id: TokenId, reason: String) {
+        self.call(caller, NotenCall::file_appeal(token_id, &reason), Self::get_now_u64())
+    }
+    pub fn review_appeal(&mut self, caller: &AccountHash, token_id: TokenId) {
+        self.call(caller, NotenCall::review_appeal(token_id), Self::get_now_u64())
+    }
+    pub fn uphold_appeal(&mut self, caller: &AccountHash, token_id: TokenId, grade: u32) {
+        self.call(caller, NotenCall::uphold_appeal(token_id, grade), Self::get_now_u64())
+    }
+    pub fn reject_appeal(&mut self, caller: &AccountHash, token_id: TokenId) {
+        self.call(caller, NotenCall::reject_appeal(token_id), Self::get_now_u64())
+    }
+    pub fn add_reviewer<T: Into<Key>>(&mut self, caller: &AccountHash, reviewer: T) {
+        self.call(caller, NotenCall::add_reviewer(reviewer.into()), Self::get_now_u64())
+    }
+
+    pub fn revoke_grade(&mut self, caller: &AccountHash, token_id: TokenId) {
+        self.call(caller, NotenCall::revoke_grade(token_id), Self::get_now_u64())
+    }
+    pub fn propose_remove_teacher<T: Into<Key>>(&mut self, caller: &AccountHash, teacher: T, deadline: u64) {
+        self.call(caller, NotenCall::propose_remove_teacher(teacher.into(), deadline), Self::get_now_u64())
+    }
+    pub fn propose_revoke_grade(&mut self, caller: &AccountHash, token_id: TokenId, deadline: u64) {
+        self.call(caller, NotenCall::propose_revoke_grade(token_id, deadline), Self::get_now_u64())
+    }
+    pub fn propose_approval_threshold(&mut self, caller: &AccountHash, threshold: u32, deadline: u64) {
+        self.call(caller, NotenCall::propose_approval_threshold(threshold, deadline), Self::get_now_u64())
+    }
+    pub fn rollover_year(&mut self, caller: &AccountHash, year: u32, archive: String, limit: u32) {
+        self.call(caller, NotenCall::rollover_year(year, &archive, limit), Self::get_now_u64())
+    }
+    pub fn pause(&mut self, caller: &AccountHash) {
+        self.call(caller, NotenCall::pause(), Self::get_now_u64())
+    }
+    pub fn propose_unpause(&mut self, caller: &AccountHash, deadline: u64) {
+        self.call(caller, NotenCall::propose_unpause(deadline), Self::get_now_u64())
+    }
+    pub fn approve_proposal(&mut self, caller: &AccountHash, proposal_id: u32, time: u64) {
+        self.call(caller, NotenCall::approve_proposal(proposal_id), time)
     }
 
     pub fn query<T: FromBytes + CLTyped>(
@@ -203,7 +501,7 @@ impl NotenContract {
             .with_deploy_hash(rand::thread_rng().gen());
 
         deploy_builder = match source {
-            DeploySource::Code(path) => deploy_builder.with_session_code(path, args),
+            DeploySource::Code(path) => deploy_builder.with_session_code(wasm_path(path), args),
             DeploySource::ByContractHash { hash, method } => {
                 deploy_builder.with_stored_session_hash(*hash, method.as_str(), args)
             }
@@ -293,7 +591,7 @@ impl NotenContract {
                               Some(dict_name.to_string()),
                               key
         )
-            .expect("should be stored value.")
+            .ok()?
             .as_cl_value()
             .expect("should be cl value.")
             .clone()
@@ -301,35 +599,66 @@ impl NotenContract {
             .expect("Wrong type in query result.")
     }
 
+    /// Like `query_dictionary`, for the contract's own types, which are stored as `CLType::Any`.
+    fn query_dictionary_any<T: FromBytes>(
+        &self,
+        contract: &ContractHash,
+        dict_name: &str,
+        key: String,
+    ) -> Option<T> {
+        let value = Self::query_dictionary_item(&self.builder,
+                              Key::Hash(contract.value()),
+                              Some(dict_name.to_string()),
+                              key
+        ).ok()?;
+        decode::dictionary_value(value.as_cl_value().expect("should be cl value.")).expect("Wrong type in query result.")
+    }
+
     /// Wrapper function for calling an entrypoint on the contract with the access rights of the deployer.
-    pub fn call(&mut self, caller: &AccountHash, method: &str, args: RuntimeArgs, time: u64) {
+    pub fn call(&mut self, caller: &AccountHash, call: NotenCall, time: u64) {
         Self::deploy(
             &mut self.builder,
             caller,
             &DeploySource::ByPackageHash {
                 package_hash: self.noten.1.clone(),
-                method: method.to_string(),
+                method: call.entry_point,
             },
-            args,
+            call.args,
             true,
             Some(time),
         );
     }
 
-    pub fn key_and_value_to_str<T: CLTyped + ToBytes>(key: &Key, value: &T) -> String {
-        let mut hasher = VarBlake2b::new(32).unwrap();
-        hasher.update(key.to_bytes().unwrap().as_slice());
-        hasher.update(value.to_bytes().unwrap().as_slice());
-        let mut ret = [0u8; 32];
-        hasher.finalize_variable(|hash| ret.clone_from_slice(hash));
-        hex::encode(ret)
+    /// Like `call`, for calls that have to fail. Returns the error the contract reverted with, or
+    /// `None` if the call failed for another reason.
+    pub fn call_failing(&mut self, caller: &AccountHash, call: NotenCall, time: u64) -> Option<NotenError> {
+        Self::deploy(
+            &mut self.builder,
+            caller,
+            &DeploySource::ByPackageHash {
+                package_hash: self.noten.1.clone(),
+                method: call.entry_point,
+            },
+            call.args,
+            false,
+            Some(time),
+        );
+        match self.builder.get_error()? {
+            engine_state::Error::Exec(execution::Error::Revert(error)) => NotenError::from_api_error(error),
+            _ => None,
+        }
+    }
+
+    /// The gas the last call consumed.
+    pub fn last_gas(&self) -> u64 {
+        self.builder.last_exec_gas_cost().value().as_u64()
     }
 
     pub fn get_token_by_index<T: Into<Key>>(&self, account: T, index: U256) -> Option<TokenId> {
         Self::query_dictionary_item(&self.builder,
                               Key::Hash(self.noten.0.value()),
-                              Some("owned_tokens_by_index".to_string()),
-                              Self::key_and_value_to_str(&account.into(), &index)
+                              Some(keys::OWNED_TOKENS_BY_INDEX.to_string()),
+                              keys::owned_token_key(&account.into(), index)
         )
             .expect("should be stored value.")
             .as_cl_value()
@@ -338,4 +667,123 @@ impl NotenContract {
             .into_t()
             .expect("Wrong type in query result.")
     }
+
+    /// The tokens `owner` holds, in the order of the owner index.
+    pub fn tokens_of<T: Into<Key>>(&self, owner: T) -> Vec<TokenId> {
+        let owner = owner.into();
+        (0u64..)
+            .map_while(|index| {
+                Self::query_dictionary_item(&self.builder,
+                                      Key::Hash(self.noten.0.value()),
+                                      Some(keys::OWNED_TOKENS_BY_INDEX.to_string()),
+                                      keys::owned_token_key(&owner, U256::from(index))
+                )
+                    .ok()?
+                    .as_cl_value()?
+                    .clone()
+                    .into_t::<Option<TokenId>>()
+                    .ok()?
+            })
+            .collect()
+    }
+
+    pub fn balance_of<T: Into<Key>>(&self, owner: T) -> U256 {
+        self.query_dictionary(&self.noten.0, keys::BALANCES, keys::key_to_str(&owner.into())).unwrap_or_default()
+    }
+
+    pub fn grade_record(&self, token_id: TokenId) -> Option<GradeRecord> {
+        self.query_dictionary_any(&self.noten.0, keys::GRADE_RECORDS, token_id.to_string())
+    }
+
+    pub fn is_revoked(&self, token_id: TokenId) -> bool {
+        self.query_dictionary(&self.noten.0, keys::REVOKED_GRADES, token_id.to_string()).unwrap_or_default()
+    }
+
+    pub fn grade_payload(&self, token_id: TokenId) -> Option<(Vec<PublicKey>, Bytes)> {
+        self.query_dictionary(&self.noten.0, keys::GRADE_PAYLOADS, keys::token_key(token_id))
+    }
+
+    pub fn pseudonym_account(&self, pseudonym: Key) -> Option<Key> {
+        self.query_dictionary(&self.noten.0, keys::PSEUDONYM_ACCOUNTS, keys::key_to_str(&pseudonym))
+    }
+
+    pub fn acknowledgement<T: Into<Key>>(&self, token_id: TokenId, guardian: T) -> Option<u64> {
+        self.query_dictionary(&self.noten.0, keys::ACKNOWLEDGEMENTS, keys::acknowledgement_key(&guardian.into(), token_id))
+    }
+
+    pub fn token_meta(&self, token_id: TokenId) -> Option<Meta> {
+        self.query_dictionary(&self.noten.0, keys::METADATA, keys::token_key(token_id))
+    }
+
+    pub fn student_class<T: Into<Key>>(&self, student: T) -> Option<String> {
+        self.query_dictionary(&self.noten.0, keys::STUDENT_CLASSES, keys::key_to_str(&student.into()))
+    }
+
+    pub fn graduation<T: Into<Key>>(&self, student: T) -> Option<u32> {
+        self.query_dictionary(&self.noten.0, keys::GRADUATES, keys::key_to_str(&student.into()))
+    }
+
+    pub fn final_average<T: Into<Key>>(&self, student: T, subject: &str, year: u32) -> Option<u32> {
+        let key = keys::subject_year_key(&student.into(), subject, year);
+        self.query_dictionary(&self.noten.0, keys::FINAL_AVERAGES, key)
+    }
+
+    pub fn year_archive(&self, year: u32) -> Option<(String, u64, u64)> {
+        self.query_dictionary(&self.noten.0, keys::YEAR_ARCHIVES, year.to_string())
+    }
+
+    /// The sum and histogram of an exam's grades.
+    pub fn exam_stats(&self, exam_id: u32) -> Option<(u64, Vec<u32>)> {
+        self.query_dictionary_any(&self.noten.0, keys::EXAM_STATS, exam_id.to_string())
+    }
+
+    pub fn indexed_grade(&self, position: u64) -> Option<TokenId> {
+        self.query_dictionary(&self.noten.0, keys::GRADES, position.to_string())
+    }
+
+    pub fn event(&self, index: u64) -> Option<Meta> {
+        self.query_dictionary(&self.noten.0, keys::EVENTS, index.to_string())
+    }
+
+    pub fn staff_event(&self, index: u64) -> Option<Meta> {
+        self.query_dictionary(&self.noten.0, keys::STAFF_EVENTS, index.to_string())
+    }
+
+    pub fn is_teacher<T: Into<Key>>(&self, account: T) -> bool {
+        self.query_dictionary(&self.noten.0, keys::ADMINS, keys::key_to_str(&account.into()))
+            .unwrap_or_default()
+    }
+
+    pub fn named_key<T: CLTyped + FromBytes>(&self, name: &str) -> T {
+        Self::query(&self.builder, Key::Hash(self.noten.0.value()), &[name.to_string()])
+    }
+}
+
+impl StateReader for NotenContract {
+    fn named_key(&self, name: &str) -> Option<CLValue> {
+        self.builder
+            .query(None, Key::Hash(self.noten.0.value()), &[name.to_string()])
+            .ok()?
+            .as_cl_value()
+            .cloned()
+    }
+
+    fn dictionary_item(&self, dictionary: &str, key: &str) -> Option<CLValue> {
+        Self::query_dictionary_item(&self.builder, Key::Hash(self.noten.0.value()), Some(dictionary.to_string()), key.to_string())
+            .ok()?
+            .as_cl_value()
+            .cloned()
+    }
+
+    fn contract_package(&self) -> Option<ContractPackageHash> {
+        let package = match self.builder.query(None, Key::Hash(self.noten.0.value()), &[]).ok()? {
+            StoredValue::Contract(contract) => contract.contract_package_hash(),
+            _ => return None,
+        };
+        let current = match self.builder.query(None, Key::Hash(package.value()), &[]).ok()? {
+            StoredValue::ContractPackage(package) => package.current_contract_hash()?,
+            _ => return None,
+        };
+        (current == self.noten.0).then(|| package)
+    }
 }
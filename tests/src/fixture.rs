@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+
+use casper_types::account::AccountHash;
+use casper_types::Key;
+use cep47::TokenId;
+use noten_client::appointment::Appointment;
+use noten_client::{NotenCall, NotenError};
+
+use crate::contract::NotenContract;
+
+/// The school year the fixture's grades are given in.
+pub const YEAR: u32 = 2026;
+
+/// A deployed contract with its accounts declared by name and role, e.g.
+///
+/// ```ignore
+/// let mut school = Fixture::builder()
+///     .teacher("ali", &["maths"])
+///     .student("bob", "2a")
+///     .guardian("biff", "bob")
+///     .build();
+/// let token_id = school.grade("ali", "bob", "maths", 45);
+/// school.fails("biff", NotenCall::revoke_grade(token_id), NotenError::NotTeacher);
+/// ```
+///
+/// Every fixture has an admin named `admin`, the account that deployed the contract.
+pub struct Fixture {
+    pub contract: NotenContract,
+    accounts: BTreeMap<String, AccountHash>,
+}
+
+#[derive(Default)]
+pub struct FixtureBuilder {
+    admins: Vec<String>,
+    teachers: Vec<(String, Vec<String>)>,
+    students: Vec<(String, String)>,
+    guardians: Vec<(String, String)>,
+    others: Vec<String>,
+}
+
+impl FixtureBuilder {
+    /// A teacher without restrictions, who can also manage staff.
+    pub fn admin(mut self, name: &str) -> Self {
+        self.admins.push(name.to_string());
+        self
+    }
+
+    /// A teacher appointed for `subjects` only.
+    pub fn teacher(mut self, name: &str, subjects: &[&str]) -> Self {
+        self.teachers.push((name.to_string(), subjects.iter().map(|subject| subject.to_string()).collect()));
+        self
+    }
+
+    pub fn student(mut self, name: &str, class: &str) -> Self {
+        self.students.push((name.to_string(), class.to_string()));
+        self
+    }
+
+    /// A guardian of the student `student`, who has to be declared as well.
+    pub fn guardian(mut self, name: &str, student: &str) -> Self {
+        self.guardians.push((name.to_string(), student.to_string()));
+        self
+    }
+
+    /// An account without any role.
+    pub fn account(mut self, name: &str) -> Self {
+        self.others.push(name.to_string());
+        self
+    }
+
+    pub fn build(self) -> Fixture {
+        let contract = NotenContract::deploy_noten();
+        let admin = contract.accounts.0;
+        let mut fixture = Fixture { contract, accounts: BTreeMap::new() };
+        fixture.accounts.insert("admin".to_string(), admin);
+
+        let names = self.admins.iter()
+            .chain(self.teachers.iter().map(|(name, _)| name))
+            .chain(self.students.iter().map(|(name, _)| name))
+            .chain(self.guardians.iter().map(|(name, _)| name))
+            .chain(self.others.iter());
+        for name in names {
+            assert!(!fixture.accounts.contains_key(name), "{} is declared twice", name);
+            let account = fixture.contract.add_account();
+            fixture.accounts.insert(name.clone(), account);
+        }
+
+        for name in &self.admins {
+            fixture.call("admin", NotenCall::add_teacher(fixture.key(name)));
+        }
+        for (name, subjects) in &self.teachers {
+            let appointment = Appointment { start: 0, end: u64::MAX, subjects: subjects.clone(), classes: vec![] };
+            fixture.call("admin", NotenCall::appoint_teacher(fixture.key(name), &appointment));
+        }
+        for (name, class) in &self.students {
+            fixture.call("admin", NotenCall::set_student_class(fixture.key(name), class));
+        }
+        for (name, student) in &self.guardians {
+            fixture.call("admin", NotenCall::add_guardian(fixture.key(student), fixture.key(name)));
+        }
+        fixture
+    }
+}
+
+impl Fixture {
+    pub fn builder() -> FixtureBuilder {
+        FixtureBuilder::default()
+    }
+
+    pub fn account(&self, name: &str) -> AccountHash {
+        *self.accounts.get(name).unwrap_or_else(|| panic!("no account named {}", name))
+    }
+
+    pub fn key(&self, name: &str) -> Key {
+        Key::Account(self.account(name))
+    }
+
+    /// Calls the contract as `caller`, which has to succeed.
+    pub fn call(&mut self, caller: &str, call: NotenCall) {
+        let caller = self.account(caller);
+        self.contract.call(&caller, call, NotenContract::get_now_u64());
+    }
+
+    /// Calls the contract as `caller`, which has to fail with `error`.
+    pub fn fails(&mut self, caller: &str, call: NotenCall, error: NotenError) {
+        let caller = self.account(caller);
+        let entry_point = call.entry_point.clone();
+        let reverted = self.contract.call_failing(&caller, call, NotenContract::get_now_u64());
+        assert_eq!(reverted, Some(error), "{} should have failed with {:?}", entry_point, error);
+    }
+
+    /// Grades `student` as `teacher` in `YEAR` and returns the grade's token id.
+    pub fn grade(&mut self, teacher: &str, student: &str, subject: &str, grade: u32) -> TokenId {
+        self.call(teacher, NotenCall::grade(self.key(student), subject, YEAR, "exam", grade));
+        *self.tokens(student).last().expect("the student has the grade")
+    }
+
+    /// The tokens `student` owns, in the order of the owner index.
+    pub fn tokens(&self, student: &str) -> Vec<TokenId> {
+        self.contract.tokens_of(self.account(student))
+    }
+}
@@ -0,0 +1,201 @@
+//! Gas every entry point costs as a student's grades and the contract's logs grow, compared with
+//! the costs stored in `gas-baseline.json`.
+//!
+//! The benchmark is ignored by `cargo test`, `make bench` runs it. It fails if a call costs more
+//! than `TOLERANCE` percent over its baseline, or if there is no baseline. `make bench-baseline`
+//! (`NOTEN_GAS_BASELINE=update`) writes the baseline after an intended change in costs.
+
+use std::collections::BTreeMap;
+use std::{env, fs};
+
+use casper_types::Key;
+use noten_client::appointment::Appointment;
+use noten_client::exam::Exam;
+use noten_client::origin::Recognition;
+use noten_client::scale::GradingScale;
+use noten_client::{payload, pseudonym, NotenCall};
+
+use crate::contract::NotenContract;
+use crate::fixture::{Fixture, YEAR};
+
+const BASELINE: &str = "gas-baseline.json";
+const TOLERANCE: u64 = 5;
+/// How many grades the student has before each round of calls.
+const STATE_SIZES: [usize; 3] = [0, 10, 50];
+
+/// Gas per call, keyed by `<entry point>/<state size>`.
+type Costs = BTreeMap<String, u64>;
+
+struct Round<'a> {
+    school: Fixture,
+    size: usize,
+    costs: &'a mut Costs,
+}
+
+impl Round<'_> {
+    fn record(&mut self, entry_point: &str) {
+        self.costs.insert(format!("{}/{}", entry_point, self.size), self.school.contract.last_gas());
+    }
+
+    fn call(&mut self, caller: &str, call: NotenCall) {
+        let entry_point = call.entry_point.clone();
+        self.school.call(caller, call);
+        self.record(&entry_point);
+    }
+}
+
+fn measure(size: usize, costs: &mut Costs) {
+    let school = Fixture::builder()
+        .student("bob", "2a")
+        .guardian("biff", "bob")
+        .account("ali")
+        .account("tim")
+        .account("dan")
+        .build();
+    let mut round = Round { school, size, costs };
+    let (bob, ali, tim, dan) = (round.school.key("bob"), round.school.key("ali"), round.school.key("tim"), round.school.key("dan"));
+    let deadline = NotenContract::get_now_u64() + 3_600_000;
+
+    // The scale can only be set, and legacy grades imported, before the first grade.
+    if size == 0 {
+        round.call("admin", NotenCall::set_grading_scale(GradingScale { min: 10, max: 60, step: 5 }));
+    }
+    round.call("admin", NotenCall::import_legacy_grade(bob, "maths", YEAR, "exam", 45, Key::Hash([7; 32])));
+    let legacy = *round.school.tokens("bob").last().unwrap();
+    round.call("admin", NotenCall::migrate(vec![legacy]));
+    for _ in 0..size {
+        round.school.grade("admin", "bob", "maths", 45);
+    }
+
+    round.call("admin", NotenCall::name());
+    round.call("admin", NotenCall::symbol());
+    round.call("admin", NotenCall::meta());
+    round.call("admin", NotenCall::grading_scale());
+
+    let token_id = round.school.grade("admin", "bob", "maths", 50);
+    round.record("grade");
+    round.call("admin", NotenCall::update_grade(bob, 55, token_id));
+    round.call("admin", NotenCall::token_meta(token_id));
+    round.call("admin", NotenCall::grade_record(token_id));
+    round.call("admin", NotenCall::grade_origin(token_id));
+    round.call("admin", NotenCall::average(bob, "maths", YEAR));
+
+    let contract = &round.school.contract;
+    let recipients = vec![contract.public_key(&round.school.account("bob")), contract.public_key(&round.school.account("biff"))];
+    let payload = payload::encrypt(&recipients, b"5.5").unwrap();
+    round.call("admin", NotenCall::set_grade_payload(token_id, recipients, payload));
+    round.call("admin", NotenCall::grade_payload(token_id));
+    round.call("biff", NotenCall::acknowledge_grade(token_id));
+    round.call("admin", NotenCall::acknowledgement(token_id, round.school.key("biff")));
+
+    // Appeals are decided by someone else than the teacher who gave the grade.
+    round.call("admin", NotenCall::add_reviewer(tim));
+    round.call("bob", NotenCall::file_appeal(token_id, "Aufgabe 2 wurde falsch korrigiert"));
+    round.call("admin", NotenCall::review_appeal(token_id));
+    round.call("tim", NotenCall::reject_appeal(token_id));
+    round.call("admin", NotenCall::appeal(token_id));
+    let upheld = round.school.grade("admin", "bob", "maths", 40);
+    round.call("bob", NotenCall::file_appeal(upheld, "Aufgabe 3 fehlt"));
+    round.call("tim", NotenCall::review_appeal(upheld));
+    round.call("tim", NotenCall::uphold_appeal(upheld, 45));
+    round.call("admin", NotenCall::remove_reviewer(tim));
+    round.call("admin", NotenCall::revoke_grade(token_id));
+
+    round.call("admin", NotenCall::add_teacher(ali));
+    let appointment = Appointment { start: 0, end: u64::MAX, subjects: vec!["maths".to_string()], classes: vec![] };
+    round.call("admin", NotenCall::appoint_teacher(ali, &appointment));
+    round.call("admin", NotenCall::appointment(ali));
+    round.call("admin", NotenCall::remove_teacher(ali));
+    round.call("admin", NotenCall::set_student_class(bob, "2a"));
+    round.call("admin", NotenCall::student_class(bob));
+    round.call("admin", NotenCall::add_guardian(bob, ali));
+    round.call("admin", NotenCall::guardians(bob));
+    round.call("admin", NotenCall::remove_guardian(bob, ali));
+
+    let alias = pseudonym::pseudonym("2019-0042", "zuger-salt");
+    round.call("admin", NotenCall::link_pseudonym(alias, pseudonym::commitment(&alias, &round.school.account("dan"), "letter-7")));
+    round.call("dan", NotenCall::claim_pseudonym(alias, "letter-7"));
+    round.call("admin", NotenCall::pseudonym_account(alias));
+
+    let exam = Exam { subject: "maths".to_string(), class: "2a".to_string(), year: YEAR, date: 0, exam_type: "exam".to_string() };
+    round.call("admin", NotenCall::register_exam(&exam));
+    round.call("admin", NotenCall::grade_exam(bob, 0, 45));
+    round.call("admin", NotenCall::exam(0));
+    round.call("admin", NotenCall::exam_summary(0));
+    let recognition = Recognition {
+        institution: "Kantonsschule Luzern".to_string(),
+        scale: "CH 1-6".to_string(),
+        value: "5".to_string(),
+        rule: "identity".to_string(),
+    };
+    round.call("admin", NotenCall::recognise_grade(bob, "english", YEAR, 50, &recognition));
+
+    round.call("admin", NotenCall::grades_page(0, 10));
+    round.call("admin", NotenCall::grades_since(0, 10));
+    round.call("admin", NotenCall::staff_events_since(0, 10));
+    round.call("admin", NotenCall::pause());
+    round.call("admin", NotenCall::propose_unpause(deadline));
+
+    round.call("admin", NotenCall::set_final_class_level(4));
+    round.call("admin", NotenCall::rollover_year(YEAR, "archive", 100));
+    round.call("admin", NotenCall::final_average(bob, "maths", YEAR));
+    round.call("admin", NotenCall::graduation(bob));
+    round.call("admin", NotenCall::year_archive(YEAR));
+    round.call("admin", NotenCall::reopen_term(YEAR));
+
+    // With a threshold of one, proposals execute right away; the last one waits for a second approval.
+    round.call("admin", NotenCall::add_teacher(tim));
+    round.call("admin", NotenCall::propose_revoke_grade(upheld, deadline));
+    round.call("admin", NotenCall::propose_reopen_term(YEAR, deadline));
+    round.call("admin", NotenCall::propose_unpause(deadline));
+    round.call("admin", NotenCall::propose_appoint_teacher(ali, &appointment, deadline));
+    round.call("admin", NotenCall::propose_add_teacher(ali, deadline));
+    round.call("admin", NotenCall::propose_approval_threshold(2, deadline));
+    round.call("admin", NotenCall::propose_remove_teacher(ali, deadline));
+    let proposal_id = round.school.contract.named_key::<u32>("proposal_count") - 1;
+    round.call("admin", NotenCall::proposal(proposal_id));
+    round.call("tim", NotenCall::approve_proposal(proposal_id));
+
+    let second = round.school.grade("admin", "bob", "french", 50);
+    let ali_account = round.school.account("ali");
+    let receiving = NotenContract::deploy_nft(&mut round.school.contract.builder, &ali_account);
+    round.call("admin", NotenCall::issue_transfer(bob, vec![second], Key::Hash(receiving.1.value())));
+    round.call("admin", NotenCall::transfer_record(0));
+    let admin_account = round.school.account("admin");
+    let registry = round.school.contract.register_for_transfers(&admin_account);
+    let sending = std::mem::replace(&mut round.school.contract.noten, receiving);
+    round.call("ali", NotenCall::set_registry(Key::Hash(registry.value())));
+    round.call("ali", NotenCall::import_transfer(Key::Hash(sending.1.value()), 0, dan));
+}
+
+#[test]
+#[ignore]
+fn gas_costs() {
+    let mut costs = Costs::new();
+    for size in STATE_SIZES {
+        measure(size, &mut costs);
+    }
+    for (call, cost) in &costs {
+        println!("{:<28} {:>14}", call, cost);
+    }
+
+    let update = env::var("NOTEN_GAS_BASELINE").map_or(false, |value| value == "update");
+    if update {
+        fs::write(BASELINE, serde_json::to_string_pretty(&costs).unwrap() + "\n").unwrap();
+        println!("wrote {}", BASELINE);
+        return;
+    }
+    let baseline = fs::read_to_string(BASELINE)
+        .unwrap_or_else(|error| panic!("{}: {}, run make bench-baseline to write it", BASELINE, error));
+    let baseline: Costs = serde_json::from_str(&baseline).unwrap();
+    let missing: Vec<&String> = costs.keys().filter(|call| !baseline.contains_key(*call)).collect();
+    assert!(missing.is_empty(), "no baseline for {:?}, run make bench-baseline", missing);
+    let regressions: Vec<String> = costs
+        .iter()
+        .filter_map(|(call, &cost)| {
+            let stored = baseline[call];
+            (cost * 100 > stored * (100 + TOLERANCE)).then(|| format!("{}: {} gas, {} in the baseline", call, cost, stored))
+        })
+        .collect();
+    assert!(regressions.is_empty(), "calls cost more than {}% over the baseline:\n{}", TOLERANCE, regressions.join("\n"));
+}
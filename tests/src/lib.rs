@@ -1,7 +1,32 @@
-use casper_types::U256;
-use crate::contract::NotenContract;
+use std::path::Path;
+use std::sync::mpsc;
+use std::{env, fs, io, process, thread};
+
+use casper_types::{ContractPackageHash, Key, PublicKey, U256};
+use cep47::TokenId;
+use maplit::btreemap;
+use noten_client::{NotenCall, NotenError};
+use noten_client::appointment::Appointment;
+use noten_client::exam::{Exam, ExamStats};
+use noten_client::keys;
+use noten_client::origin::Recognition;
+use noten_client::page::{page, MAX_PAGE_SIZE};
+use noten_client::rpc::RpcState;
+use noten_client::state::read_dictionary_item;
+use noten_client::{import, payload, pseudonym};
+use noten_client::scale::GradingScale;
+use noten_client::credential::{self, CredentialError};
+use noten_client::transcript::Transcript;
+use noten_devnode::DevNode;
+use noten_indexer::Indexer;
+use serde_json::{json, Value};
+use crate::contract::{wasm_path, NotenContract};
+use crate::fixture::Fixture;
 
 mod contract;
+mod fixture;
+mod gas;
+mod properties;
 
 #[test]
 fn deploy() {
@@ -9,12 +34,20 @@ fn deploy() {
 }
 
 #[test]
-#[should_panic]
+fn error_codes_round_trip() {
+    for code in 20..=61 {
+        let error = NotenError::from_code(code).unwrap_or_else(|| panic!("no error with code {}", code));
+        assert_eq!(error as u16, code);
+    }
+    assert_eq!(NotenError::from_code(19), None);
+    assert_eq!(NotenError::from_code(62), None);
+}
+
+#[test]
 fn add_teacher_by_non_admin() {
-    let mut contract = NotenContract::deploy_noten();
-    let (_admin, _biff, tim, ali, _bob, _dan) = contract.accounts;
+    let mut school = Fixture::builder().account("tim").account("ali").build();
 
-    contract.add_teacher(&tim, ali);
+    school.fails("tim", NotenCall::add_teacher(school.key("ali")), NotenError::NotTeacher);
 }
 
 #[test]
@@ -68,11 +101,1297 @@ fn teacher_gives_grade() {
 }
 
 #[test]
-#[should_panic]
 fn student_gives_grade() {
+    let mut school = Fixture::builder().admin("ali").student("bob", "2a").student("dan", "2a").build();
+
+    school.fails("dan", NotenCall::grade(school.key("bob"), "maths", 4, "project", 30), NotenError::NotTeacher);
+}
+
+#[test]
+fn teacher_grades_outside_appointment() {
+    let mut school = Fixture::builder().teacher("ali", &["maths"]).student("bob", "2a").build();
+
+    school.grade("ali", "bob", "maths", 45);
+    school.fails("ali", NotenCall::grade(school.key("bob"), "french", fixture::YEAR, "exam", 45), NotenError::OutsideAppointment);
+    assert_eq!(school.tokens("bob").len(), 1);
+}
+
+#[test]
+fn guardian_decrypts_grade_payload() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "project".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let recipients = vec![contract.public_key(&bob), contract.public_key(&biff)];
+    let payload = payload::encrypt(&recipients, b"Gut gemacht, aber Aufgabe 3 fehlt.").unwrap();
+    contract.set_grade_payload(&ali, token_id, recipients, payload);
+
+    let (recipients, payload) = contract.grade_payload(token_id).unwrap();
+    let plaintext = payload::decrypt(&contract.secret_keys[&biff], &recipients, &payload).unwrap();
+    assert_eq!(plaintext, b"Gut gemacht, aber Aufgabe 3 fehlt.");
+    assert_eq!(
+        payload::decrypt(&contract.secret_keys[&ali], &recipients, &payload),
+        Err(payload::PayloadError::NotARecipient)
+    );
+}
+
+#[test]
+#[should_panic]
+fn grade_payload_without_student() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "project".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let recipients = vec![contract.public_key(&biff)];
+    let payload = payload::encrypt(&recipients, b"5.5").unwrap();
+    contract.set_grade_payload(&ali, token_id, recipients, payload);
+}
+
+#[test]
+fn student_claims_pseudonym() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+    let alias = pseudonym::pseudonym("2019-0042", "zuger-salt");
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, alias, "maths".to_string(), 4, "project".to_string(), 30);
+    assert!(contract.get_token_by_index(alias, U256::zero()).is_some());
+    assert_eq!(contract.pseudonym_account(alias), None);
+
+    contract.link_pseudonym(&admin, alias, pseudonym::commitment(&alias, &bob, "letter-7"));
+    contract.claim_pseudonym(&bob, alias, "letter-7".to_string());
+    assert_eq!(contract.pseudonym_account(alias), Some(Key::Account(bob)));
+}
+
+#[test]
+#[should_panic]
+fn claim_pseudonym_of_other_student() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, _ali, bob, dan) = contract.accounts;
+    let alias = pseudonym::pseudonym("2019-0042", "zuger-salt");
+
+    contract.link_pseudonym(&admin, alias, pseudonym::commitment(&alias, &bob, "letter-7"));
+    contract.claim_pseudonym(&dan, alias, "letter-7".to_string());
+}
+
+#[test]
+fn guardian_acknowledges_grade() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.add_guardian(&admin, bob, biff);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    assert_eq!(contract.acknowledgement(token_id, biff), None);
+
+    contract.acknowledge_grade(&biff, token_id);
+    assert!(contract.acknowledgement(token_id, biff).is_some());
+}
+
+#[test]
+fn non_guardian_acknowledges_grade() {
+    let mut school = Fixture::builder().admin("ali").student("bob", "2a").student("dan", "2a").guardian("biff", "bob").build();
+
+    let token_id = school.grade("ali", "bob", "maths", 30);
+    school.fails("dan", NotenCall::acknowledge_grade(token_id), NotenError::NotGuardian);
+    school.call("biff", NotenCall::acknowledge_grade(token_id));
+}
+
+#[test]
+#[should_panic]
+fn grade_payload_without_guardian() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.add_guardian(&admin, bob, biff);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let recipients = vec![contract.public_key(&bob)];
+    let payload = payload::encrypt(&recipients, b"5.5").unwrap();
+    contract.set_grade_payload(&ali, token_id, recipients, payload);
+}
+
+#[test]
+fn teacher_updates_grade() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    contract.update_grade(&ali, bob, 45, token_id);
+    assert_eq!(contract.token_meta(token_id).unwrap()["grade"], "45");
+}
+
+#[test]
+fn upheld_appeal_updates_grade() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.add_reviewer(&admin, tim);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+
+    contract.file_appeal(&bob, token_id, "Aufgabe 2 wurde falsch korrigiert".to_string());
+    contract.review_appeal(&tim, token_id);
+    contract.uphold_appeal(&tim, token_id, 40);
+    assert_eq!(contract.token_meta(token_id).unwrap()["grade"], "40");
+}
+
+#[test]
+#[should_panic]
+fn appeal_decided_by_student() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+
+    contract.file_appeal(&bob, token_id, "Aufgabe 2 wurde falsch korrigiert".to_string());
+    contract.review_appeal(&ali, token_id);
+    contract.uphold_appeal(&bob, token_id, 60);
+}
+
+#[test]
+fn appeal_filed_by_other_student() {
+    let mut school = Fixture::builder().admin("ali").student("bob", "2a").student("dan", "2a").build();
+
+    let token_id = school.grade("ali", "bob", "maths", 30);
+    school.fails("dan", NotenCall::file_appeal(token_id, "unfair"), NotenError::NotAppellant);
+}
+
+#[test]
+fn appeal_decided_by_impartial_teacher() {
+    let mut school = Fixture::builder().admin("ali").teacher("tim", &["french"]).student("bob", "2a").build();
+    let token_id = school.grade("ali", "bob", "maths", 30);
+    school.call("bob", NotenCall::file_appeal(token_id, "Aufgabe 2 wurde falsch korrigiert"));
+    school.call("ali", NotenCall::review_appeal(token_id));
+
+    school.fails("ali", NotenCall::uphold_appeal(token_id, 60), NotenError::OwnGrade);
+    school.fails("ali", NotenCall::reject_appeal(token_id), NotenError::OwnGrade);
+    school.fails("tim", NotenCall::uphold_appeal(token_id, 60), NotenError::OutsideAppointment);
+    school.call("admin", NotenCall::uphold_appeal(token_id, 40));
+    assert_eq!(school.contract.token_meta(token_id).unwrap()["grade"], "40");
+}
+
+#[test]
+fn teacher_removed_by_two_admins() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, tim, _ali, _bob, _dan) = contract.accounts;
+    let deadline = NotenContract::get_now_u64() + 60_000;
+
+    contract.add_teacher(&admin, biff);
+    contract.add_teacher(&admin, tim);
+    contract.propose_approval_threshold(&admin, 2, deadline);
+    assert_eq!(contract.named_key::<u32>("approval_threshold"), 2);
+
+    contract.propose_remove_teacher(&admin, tim, deadline);
+    assert!(contract.is_teacher(tim));
+    contract.approve_proposal(&biff, 1, NotenContract::get_now_u64());
+    assert!(!contract.is_teacher(tim));
+}
+
+#[test]
+#[should_panic]
+fn single_admin_removes_teacher_above_threshold() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, _ali, _bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, tim);
+    contract.propose_approval_threshold(&admin, 2, NotenContract::get_now_u64() + 60_000);
+    contract.remove_teacher(&admin, tim);
+}
+
+#[test]
+#[should_panic]
+fn expired_proposal_approved() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, tim, _ali, _bob, _dan) = contract.accounts;
+    let deadline = NotenContract::get_now_u64() + 60_000;
+
+    contract.add_teacher(&admin, biff);
+    contract.propose_approval_threshold(&admin, 2, deadline);
+    contract.propose_remove_teacher(&admin, tim, deadline);
+    contract.approve_proposal(&biff, 1, deadline + 1);
+}
+
+#[test]
+fn staff_added_by_two_admins_above_threshold() {
+    let mut school = Fixture::builder().admin("biff").account("tim").account("ali").build();
+    let deadline = NotenContract::get_now_u64() + 60_000;
+    let appointment = Appointment { start: 0, end: u64::MAX, subjects: vec!["maths".to_string()], classes: vec![] };
+    school.call("admin", NotenCall::propose_approval_threshold(2, deadline));
+
+    school.fails("admin", NotenCall::add_teacher(school.key("tim")), NotenError::ApprovalRequired);
+    school.fails("admin", NotenCall::appoint_teacher(school.key("ali"), &appointment), NotenError::ApprovalRequired);
+
+    school.call("admin", NotenCall::propose_add_teacher(school.key("tim"), deadline));
+    school.call("admin", NotenCall::propose_appoint_teacher(school.key("ali"), &appointment, deadline));
+    assert!(!school.contract.is_teacher(school.key("tim")));
+    school.call("biff", NotenCall::approve_proposal(1));
+    school.call("biff", NotenCall::approve_proposal(2));
+    assert!(school.contract.is_teacher(school.key("tim")));
+    assert_eq!(school.contract.named_key::<u32>("permanent_teacher_count"), 3);
+}
+
+#[test]
+fn threshold_above_permanent_teachers() {
+    let mut school = Fixture::builder().admin("biff").teacher("tim", &["maths"]).build();
+    let deadline = NotenContract::get_now_u64() + 60_000;
+
+    // Substitutes don't count towards the threshold.
+    school.fails("admin", NotenCall::propose_approval_threshold(3, deadline), NotenError::InvalidThreshold);
+    school.call("admin", NotenCall::propose_approval_threshold(2, deadline));
+    // Neither can the threshold be left out of reach by removing a teacher.
+    school.call("admin", NotenCall::propose_remove_teacher(school.key("biff"), deadline));
+    school.fails("biff", NotenCall::approve_proposal(1), NotenError::InvalidThreshold);
+    assert!(school.contract.is_teacher(school.key("biff")));
+}
+
+#[test]
+#[should_panic]
+fn revoked_grade_updated() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    contract.revoke_grade(&admin, token_id);
+    assert_eq!(contract.token_meta(token_id).unwrap()["revoked"], "true");
+    contract.update_grade(&ali, bob, 45, token_id);
+}
+
+#[test]
+fn substitute_grades_during_appointment() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, _ali, bob, _dan) = contract.accounts;
+    let now = NotenContract::get_now_u64();
+
+    contract.set_student_class(&admin, bob, "2a".to_string());
+    contract.appoint_teacher(&admin, tim, now - 1_000, now + 60_000, vec!["maths".to_string()], vec!["2a".to_string()]);
+    contract.grade(&tim, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    assert!(contract.get_token_by_index(bob, U256::zero()).is_some());
+}
+
+#[test]
+#[should_panic]
+fn substitute_grades_after_appointment() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, _ali, bob, _dan) = contract.accounts;
+    let now = NotenContract::get_now_u64();
+
+    contract.appoint_teacher(&admin, tim, now - 1_000, now + 60_000, vec![], vec![]);
+    contract.grade_at(&tim, bob, "maths".to_string(), 4, "exam".to_string(), 30, now + 60_000);
+}
+
+#[test]
+#[should_panic]
+fn substitute_grades_other_subject() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, _ali, bob, _dan) = contract.accounts;
+    let now = NotenContract::get_now_u64();
+
+    contract.appoint_teacher(&admin, tim, now - 1_000, now + 60_000, vec!["maths".to_string()], vec![]);
+    contract.grade(&tim, bob, "french".to_string(), 4, "exam".to_string(), 30);
+}
+
+#[test]
+fn substitute_cannot_promote_themselves() {
+    let mut school = Fixture::builder().teacher("tim", &["maths"]).student("bob", "2a").build();
+
+    school.fails("tim", NotenCall::add_teacher(school.key("tim")), NotenError::NotPermanentTeacher);
+    // The appointment still limits them.
+    school.fails("tim", NotenCall::grade(school.key("bob"), "french", fixture::YEAR, "exam", 45), NotenError::OutsideAppointment);
+}
+
+#[test]
+fn substitute_cannot_appoint_others() {
+    let mut school = Fixture::builder().teacher("tim", &["maths"]).account("ali").build();
+    let appointment = Appointment { start: 0, end: u64::MAX, subjects: vec![], classes: vec![] };
+
+    school.fails("tim", NotenCall::add_teacher(school.key("ali")), NotenError::NotPermanentTeacher);
+    school.fails("tim", NotenCall::appoint_teacher(school.key("ali"), &appointment), NotenError::NotPermanentTeacher);
+    assert!(!school.contract.is_teacher(school.key("ali")));
+}
+
+#[test]
+fn substitute_cannot_take_sensitive_actions() {
+    let mut school = Fixture::builder().admin("biff").teacher("tim", &["maths"]).student("bob", "2a").build();
+    let deadline = NotenContract::get_now_u64() + 60_000;
+    let token_id = school.grade("tim", "bob", "maths", 45);
+
+    school.fails("tim", NotenCall::revoke_grade(token_id), NotenError::NotPermanentTeacher);
+    school.fails("tim", NotenCall::remove_teacher(school.key("biff")), NotenError::NotPermanentTeacher);
+    school.fails("tim", NotenCall::set_grading_scale(GradingScale::default()), NotenError::NotPermanentTeacher);
+    school.fails("tim", NotenCall::rollover_year(fixture::YEAR, "archive", 10), NotenError::NotPermanentTeacher);
+    school.fails("tim", NotenCall::propose_revoke_grade(token_id, deadline), NotenError::NotPermanentTeacher);
+
+    school.call("admin", NotenCall::propose_approval_threshold(2, deadline));
+    school.call("admin", NotenCall::propose_revoke_grade(token_id, deadline));
+    school.fails("tim", NotenCall::approve_proposal(1), NotenError::NotPermanentTeacher);
+    assert!(!school.contract.is_revoked(token_id));
+}
+
+#[test]
+fn grade_records_teacher_and_time() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+    let now = NotenContract::get_now_u64();
+
+    contract.add_teacher(&admin, ali);
+    contract.grade_at(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30, now);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let meta = contract.token_meta(token_id).unwrap();
+    assert_eq!(meta["teacher"], Key::Account(ali).to_formatted_string());
+    assert_eq!(meta["issued_at"], now.to_string());
+    assert_eq!(meta["contract_version"], "1");
+}
+
+#[test]
+fn upgrade_keeps_grades() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+    let previous_contract = contract.noten.0;
+
+    // A grade in the format of the first release, without a record, and one with a record.
+    contract.call(&admin, NotenCall::import_legacy_grade(bob.into(), "maths", 4, "exam", 40, Key::Hash([7; 32])), NotenContract::get_now_u64());
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    contract.upgrade_noten();
+    assert_ne!(contract.noten.0, previous_contract);
+
+    let legacy = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let token_id = contract.get_token_by_index(bob, U256::one()).unwrap();
+    assert_eq!(contract.grade_record(legacy), None);
+    contract.migrate(&admin, vec![legacy, token_id]);
+    assert_eq!(contract.named_key::<u32>("schema_version"), 4);
+    let record = contract.grade_record(legacy).unwrap();
+    assert_eq!((record.grade, record.issued_at, record.contract_version), (40, 0, 0));
+    assert_eq!(contract.token_meta(token_id).unwrap()["grade"], "30");
+    assert_eq!(contract.grade_record(token_id).unwrap().grade, 30);
+    assert_eq!(contract.grade_record(token_id).unwrap().contract_version, 1);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 45);
+    let upgraded = contract.get_token_by_index(bob, U256::from(2)).unwrap();
+    assert_eq!(contract.named_key::<u32>("contract_version"), 2);
+    assert_eq!(contract.grade_record(upgraded).unwrap().contract_version, 2);
+    assert_eq!(contract.token_meta(upgraded).unwrap()["contract_version"], "2");
+}
+
+#[test]
+fn pause_stops_changes_to_students_and_reviewers() {
+    let mut school = Fixture::builder().student("bob", "2a").account("biff").account("tim").build();
+    let bob = school.key("bob");
+    let token_id = school.grade("admin", "bob", "maths", 45);
+    let recipients = vec![school.contract.public_key(&school.account("bob"))];
+    let payload = payload::encrypt(&recipients, b"4.5").unwrap();
+    school.call("admin", NotenCall::pause());
+
+    school.fails("admin", NotenCall::add_guardian(bob, school.key("biff")), NotenError::Paused);
+    school.fails("admin", NotenCall::set_student_class(bob, "2b"), NotenError::Paused);
+    school.fails("admin", NotenCall::add_reviewer(school.key("tim")), NotenError::Paused);
+    school.fails("admin", NotenCall::set_grade_payload(token_id, recipients, payload), NotenError::Paused);
+    let alias = pseudonym::pseudonym("2019-0042", "zuger-salt");
+    school.fails("admin", NotenCall::link_pseudonym(alias, [0; 32]), NotenError::Paused);
+    school.fails("admin", NotenCall::set_grading_scale(GradingScale::default()), NotenError::Paused);
+    school.fails("admin", NotenCall::reopen_term(fixture::YEAR), NotenError::Paused);
+    school.fails("admin", NotenCall::set_final_class_level(3), NotenError::Paused);
+    // Migrating is what a school pauses for during an upgrade.
+    school.call("admin", NotenCall::migrate(vec![token_id]));
+}
+
+#[test]
+fn substitute_cannot_pause() {
+    let mut school = Fixture::builder().teacher("tim", &["maths"]).build();
+
+    school.fails("tim", NotenCall::pause(), NotenError::NotPermanentTeacher);
+    school.call("admin", NotenCall::pause());
+    // Alone, the admin can still unpause: there is no second key to ask.
+    school.call("admin", NotenCall::propose_unpause(NotenContract::get_now_u64() + 60_000));
+    assert!(!school.contract.named_key::<bool>("paused"));
+}
+
+#[test]
+#[should_panic]
+fn teacher_grades_while_paused() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.pause(&admin);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+}
+
+#[test]
+fn compromised_teacher_removed_while_paused() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, biff);
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 4, "exam".to_string(), 30);
+    contract.pause(&admin);
+    contract.remove_teacher(&admin, ali);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    assert_eq!(contract.token_meta(token_id).unwrap()["grade"], "30");
+
+    // Unpausing takes a second administrator even without a threshold.
+    contract.propose_unpause(&admin, NotenContract::get_now_u64() + 60_000);
+    assert!(contract.named_key::<bool>("paused"));
+    contract.approve_proposal(&biff, 0, NotenContract::get_now_u64());
+    contract.grade(&admin, bob, "maths".to_string(), 4, "exam".to_string(), 45);
+    assert!(!contract.is_teacher(ali));
+}
+
+#[test]
+fn rollover_promotes_and_graduates() {
     let mut contract = NotenContract::deploy_noten();
     let (admin, _biff, _tim, ali, bob, dan) = contract.accounts;
 
     contract.add_teacher(&admin, ali);
-    contract.grade(&dan, bob, "maths".to_string(), 4, "project".to_string(), 30);
-}
\ No newline at end of file
+    contract.set_student_class(&admin, bob, "2a".to_string());
+    contract.set_student_class(&admin, dan, "4b".to_string());
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 30);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 1);
+    assert_eq!(contract.year_archive(2026), None);
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 1);
+
+    assert_eq!(contract.final_average(bob, "maths", 2026), Some(38));
+    assert_eq!(contract.student_class(bob), Some("3a".to_string()));
+    assert_eq!(contract.graduation(bob), None);
+    assert_eq!(contract.graduation(dan), Some(2026));
+    let (archive, _closed_at, students) = contract.year_archive(2026).unwrap();
+    assert_eq!(archive, "archive-2026");
+    assert_eq!(students, 2);
+}
+
+#[test]
+fn year_rolled_over_once() {
+    let mut school = Fixture::builder().student("bob", "2a").build();
+
+    school.fails("admin", NotenCall::rollover_year(fixture::YEAR, "archive", 0), NotenError::InvalidLimit);
+    school.call("admin", NotenCall::rollover_year(fixture::YEAR, "archive", 10));
+    school.call("admin", NotenCall::reopen_term(fixture::YEAR));
+    school.fails("admin", NotenCall::rollover_year(fixture::YEAR, "archive", 10), NotenError::YearArchived);
+    assert_eq!(school.contract.student_class(school.key("bob")), Some("3a".to_string()));
+}
+
+#[test]
+#[should_panic]
+fn teacher_grades_closed_year() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 30);
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 10);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+}
+
+#[test]
+fn school_registers_with_canton() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, _ali, _bob, _dan) = contract.accounts;
+
+    let (registry, registry_package) = contract.deploy_registry(&admin, "ZG");
+    contract.register_school(&admin, registry_package, "Kantonsschule Zug");
+
+    let (school, name, meta, active) = contract.registered_school(&registry, 0).unwrap();
+    assert_eq!(school, Key::Hash(contract.noten.1.value()));
+    assert_eq!(name, "Kantonsschule Zug");
+    assert_eq!(meta["kanton"], "ZG");
+    assert!(active);
+    assert_eq!(contract.registered_school(&registry, 1), None);
+}
+
+#[test]
+#[should_panic]
+fn school_registers_with_other_canton() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, _ali, _bob, _dan) = contract.accounts;
+
+    let (_registry, registry_package) = contract.deploy_registry(&admin, "LU");
+    contract.register_school(&admin, registry_package, "Kantonsschule Zug");
+}
+
+#[test]
+fn registry_creates_schools() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    let (registry, registry_package) = contract.deploy_registry(&admin, "ZG");
+    contract.create_school(&admin, registry_package, "Kantonsschule Menzingen", "ZG", biff);
+
+    let (school, name, meta, active) = contract.registered_school(&registry, 0).unwrap();
+    assert_eq!(name, "Kantonsschule Menzingen");
+    assert_eq!(meta["school"], "Kantonsschule Menzingen");
+    assert!(active);
+    let package = ContractPackageHash::new(school.into_hash().unwrap());
+    let contract_hash = contract.builder.get_contract_package(package).unwrap().current_contract_hash().unwrap();
+    contract.noten = (contract_hash, package);
+
+    // The school's admin is the one the canton office named, not the registry.
+    contract.add_teacher(&biff, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    assert!(contract.get_token_by_index(bob, U256::zero()).is_some());
+}
+
+#[test]
+#[should_panic]
+fn registry_creates_schools_of_its_canton_only() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, _ali, _bob, _dan) = contract.accounts;
+
+    let (_registry, registry_package) = contract.deploy_registry(&admin, "ZG");
+    contract.create_school(&admin, registry_package, "Kantonsschule Sursee", "LU", biff);
+}
+
+#[test]
+fn transferred_grades_imported_as_external() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let receiving = NotenContract::deploy_nft(&mut contract.builder, &biff);
+    contract.issue_transfer(&ali, bob, vec![token_id], receiving.1);
+    let registry = contract.register_for_transfers(&admin);
+
+    let sending = std::mem::replace(&mut contract.noten, receiving);
+    contract.set_registry(&biff, registry);
+    contract.import_transfer(&biff, sending.1, 0, dan);
+
+    let imported = contract.get_token_by_index(dan, U256::zero()).unwrap();
+    let meta = contract.token_meta(imported).unwrap();
+    assert_eq!(meta["kind"], "external");
+    assert_eq!(meta["grade"], "45");
+    assert_eq!(meta["subject"], "maths");
+    assert_eq!(meta["school_token_id"], token_id.to_string());
+}
+
+#[test]
+fn transfer_from_unregistered_school() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let receiving = NotenContract::deploy_nft(&mut contract.builder, &biff);
+    contract.issue_transfer(&ali, bob, vec![token_id], receiving.1);
+
+    let sending = std::mem::replace(&mut contract.noten, receiving);
+    let import = NotenCall::import_transfer(Key::Hash(sending.1.value()), 0, dan.into());
+    assert_eq!(contract.call_failing(&biff, import.clone(), NotenContract::get_now_u64()), Some(NotenError::UnregisteredSchool));
+    // A registry the sending school isn't registered with doesn't vouch for it either.
+    let (_, registry) = contract.deploy_registry(&admin, "ZG");
+    contract.set_registry(&biff, registry);
+    assert_eq!(contract.call_failing(&biff, import, NotenContract::get_now_u64()), Some(NotenError::UnregisteredSchool));
+}
+
+#[test]
+#[should_panic]
+fn teacher_updates_transferred_grade() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let receiving = NotenContract::deploy_nft(&mut contract.builder, &biff);
+    contract.issue_transfer(&ali, bob, vec![token_id], receiving.1);
+    let registry = contract.register_for_transfers(&admin);
+
+    let sending = std::mem::replace(&mut contract.noten, receiving);
+    contract.set_registry(&biff, registry);
+    contract.import_transfer(&biff, sending.1, 0, dan);
+    let imported = contract.get_token_by_index(dan, U256::zero()).unwrap();
+    contract.update_grade(&biff, dan, 60, imported);
+}
+
+#[test]
+#[should_panic]
+fn transfer_imported_twice() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, _tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let receiving = NotenContract::deploy_nft(&mut contract.builder, &biff);
+    contract.issue_transfer(&ali, bob, vec![token_id], receiving.1);
+    let registry = contract.register_for_transfers(&admin);
+
+    let sending = std::mem::replace(&mut contract.noten, receiving);
+    contract.set_registry(&biff, registry);
+    contract.import_transfer(&biff, sending.1, 0, dan);
+    contract.import_transfer(&biff, sending.1, 0, dan);
+}
+
+#[test]
+fn recognised_grade_counts_in_average() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "french".to_string(), 2026, "exam".to_string(), 40);
+    contract.recognise_grade(&admin, bob, "french", 2026, 50, ("Lycée Lamartine", "0-20", "15", "linear 0-20 to 1-6"));
+
+    let token_id = contract.get_token_by_index(bob, U256::one()).unwrap();
+    let meta = contract.token_meta(token_id).unwrap();
+    assert_eq!(meta["kind"], "recognised");
+    assert_eq!(meta["institution"], "Lycée Lamartine");
+    assert_eq!(meta["original_value"], "15");
+
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 10);
+    assert_eq!(contract.final_average(bob, "french", 2026), Some(45));
+}
+
+#[test]
+fn substitute_recognises_grades_of_their_subjects_only() {
+    let mut school = Fixture::builder().teacher("tim", &["french"]).student("bob", "2a").build();
+    let bob = school.key("bob");
+    let recognition = Recognition {
+        institution: "Lycée Lamartine".to_string(),
+        scale: "0-20".to_string(),
+        value: "15".to_string(),
+        rule: "linear 0-20 to 1-6".to_string(),
+    };
+
+    school.fails("tim", NotenCall::recognise_grade(bob, "maths", 2026, 50, &recognition), NotenError::OutsideAppointment);
+    school.call("tim", NotenCall::recognise_grade(bob, "french", 2026, 50, &recognition));
+    assert_eq!(school.tokens("bob").len(), 1);
+}
+
+#[test]
+#[should_panic]
+fn teacher_updates_recognised_grade() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, _ali, bob, _dan) = contract.accounts;
+
+    contract.recognise_grade(&admin, bob, "french", 2026, 50, ("Lycée Lamartine", "0-20", "15", "linear 0-20 to 1-6"));
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    contract.update_grade(&admin, bob, 60, token_id);
+}
+
+#[test]
+fn grades_indexed_with_events() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 30);
+    contract.grade(&ali, dan, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let first = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    let second = contract.get_token_by_index(dan, U256::zero()).unwrap();
+    contract.update_grade(&ali, bob, 35, first);
+    contract.revoke_grade(&admin, second);
+
+    assert_eq!(contract.named_key::<u64>("grade_count"), 2);
+    assert_eq!(contract.indexed_grade(0), Some(first));
+    assert_eq!(contract.indexed_grade(1), Some(second));
+    assert_eq!(contract.named_key::<u64>("event_count"), 4);
+    let events: Vec<(String, String)> = (0..4)
+        .map(|index| {
+            let event = contract.event(index).unwrap();
+            (event["event"].clone(), event["token_id"].clone())
+        })
+        .collect();
+    assert_eq!(events, vec![
+        ("issued".to_string(), first.to_string()),
+        ("issued".to_string(), second.to_string()),
+        ("updated".to_string(), first.to_string()),
+        ("revoked".to_string(), second.to_string()),
+    ]);
+}
+
+#[test]
+fn grades_paged() {
+    let mut school = Fixture::builder().student("bob", "2a").build();
+    let tokens: Vec<TokenId> = [30, 45, 50].iter().map(|&grade| school.grade("admin", "bob", "maths", grade)).collect();
+    school.call("admin", NotenCall::update_grade(school.key("bob"), 55, tokens[0]));
+    let (grade_count, event_count) = (school.contract.named_key::<u64>("grade_count"), school.contract.named_key::<u64>("event_count"));
+    assert_eq!((grade_count, event_count), (3, 4));
+
+    // Pages of two list every grade and event once, in order, and stop at the end.
+    let paged = |count: u64, read: &dyn Fn(u64) -> Option<String>| {
+        let mut entries = Vec::new();
+        let mut offset = 0;
+        loop {
+            let positions = page(offset, 2, count);
+            if positions.is_empty() {
+                return entries;
+            }
+            offset = positions.end;
+            entries.extend(positions.map(|position| read(position).unwrap()));
+        }
+    };
+    let grades = paged(grade_count, &|position| school.contract.indexed_grade(position).map(|token_id| token_id.to_string()));
+    assert_eq!(grades, tokens.iter().map(TokenId::to_string).collect::<Vec<_>>());
+    let events = paged(event_count, &|index| school.contract.event(index).map(|event| event["event"].clone()));
+    assert_eq!(events, vec!["issued", "issued", "issued", "updated"]);
+
+    // An offset past the end is an empty page rather than an error, and the limit is capped.
+    assert_eq!(page(2, 2, 3), 2..3);
+    assert!(page(5, 10, 3).is_empty());
+    assert!(page(u64::MAX, u64::MAX, 3).is_empty());
+    assert_eq!(page(0, 1000, 250), 0..MAX_PAGE_SIZE);
+    assert_eq!(page(200, 1000, 250), 200..250);
+    for call in [
+        NotenCall::grades_page(5, 10),
+        NotenCall::grades_page(0, 1000),
+        NotenCall::grades_since(10, 1000),
+        NotenCall::grades_since(u64::MAX, u64::MAX),
+    ] {
+        school.call("admin", call);
+    }
+}
+
+#[test]
+fn migrate_indexes_existing_grades() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 30);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    contract.upgrade_noten();
+    contract.migrate(&admin, vec![token_id]);
+
+    assert_eq!(contract.named_key::<u64>("grade_count"), 1);
+    assert_eq!(contract.indexed_grade(0), Some(token_id));
+}
+
+#[test]
+fn legacy_grades_are_migrated() {
+    let mut school = Fixture::builder().teacher("tim", &["maths"]).student("bob", "2a").build();
+    let (bob, source) = (school.key("bob"), Key::Hash([7; 32]));
+    school.fails("tim", NotenCall::import_legacy_grade(bob, "maths", 2026, "exam", 45, source), NotenError::NotPermanentTeacher);
+
+    school.call("admin", NotenCall::import_legacy_grade(bob, "maths", 2026, "exam", 45, source));
+    let token_id = school.tokens("bob")[0];
+    assert_eq!(school.contract.grade_record(token_id), None);
+    let meta = school.contract.token_meta(token_id).unwrap();
+    assert_eq!(meta["imported_by"], school.key("admin").to_formatted_string());
+    assert_eq!(meta["source_package"], source.to_formatted_string());
+
+    school.call("admin", NotenCall::migrate(vec![token_id]));
+    let record = school.contract.grade_record(token_id).unwrap();
+    assert_eq!((record.student, record.subject.as_str(), record.year, record.grade), (bob, "maths", 2026, 45));
+    assert_eq!((record.issued_at, record.contract_version), (0, 0));
+    let meta = school.contract.token_meta(token_id).unwrap();
+    assert_eq!(meta["teacher"], record.teacher.to_formatted_string());
+    assert_eq!(school.contract.named_key::<u64>("grade_count"), 1);
+
+    // Once grading has started, imports would be grades without the checks of `grade`.
+    school.grade("admin", "bob", "maths", 50);
+    school.fails("admin", NotenCall::import_legacy_grade(bob, "maths", 2026, "exam", 60, source), NotenError::LegacyImportClosed);
+}
+
+#[test]
+fn exam_statistics_follow_grades() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, biff, tim, ali, bob, dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    for student in [bob, dan, biff] {
+        contract.set_student_class(&admin, student, "2a".to_string());
+    }
+    contract.set_student_class(&admin, tim, "2b".to_string());
+    contract.register_exam(&ali, "maths", "2a", 2026, "exam");
+    contract.grade_exam(&ali, bob, 0, 40);
+    contract.grade_exam(&ali, dan, 0, 50);
+    contract.grade_exam(&ali, biff, 0, 45);
+
+    let mut histogram = vec![0; 11];
+    histogram[6] = 1;
+    histogram[7] = 1;
+    histogram[8] = 1;
+    assert_eq!(contract.exam_stats(0), Some((135, histogram.clone())));
+
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    assert_eq!(contract.token_meta(token_id).unwrap()["exam_id"], "0");
+    contract.update_grade(&ali, bob, 55, token_id);
+    histogram[6] = 0;
+    histogram[9] = 1;
+    assert_eq!(contract.exam_stats(0), Some((150, histogram)));
+}
+
+#[test]
+#[should_panic]
+fn teacher_grades_off_scale() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 42);
+}
+
+#[test]
+#[should_panic]
+fn teacher_grades_exam_of_other_class() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, ali, _bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.set_student_class(&admin, tim, "2b".to_string());
+    contract.register_exam(&ali, "maths", "2a", 2026, "exam");
+    contract.grade_exam(&ali, tim, 0, 40);
+}
+
+#[test]
+#[should_panic]
+fn scale_changed_after_grading() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.set_grading_scale(&admin, 10, 60, 1);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 42);
+    contract.set_grading_scale(&admin, 10, 60, 5);
+}
+
+#[test]
+fn scale_changed_after_exam_registered() {
+    let mut school = Fixture::builder().student("bob", "2a").build();
+
+    school.call("admin", NotenCall::register_exam(&Exam {
+        subject: "maths".to_string(),
+        class: "2a".to_string(),
+        year: fixture::YEAR,
+        date: NotenContract::get_now_u64(),
+        exam_type: "exam".to_string(),
+    }));
+    // A wider scale would have grades past the end of the exam's histogram.
+    school.fails("admin", NotenCall::set_grading_scale(GradingScale { min: 10, max: 60, step: 1 }), NotenError::ScaleInUse);
+    school.call("admin", NotenCall::grade_exam(school.key("bob"), 0, 45));
+}
+
+#[test]
+fn scale_with_too_many_steps() {
+    let mut school = Fixture::builder().build();
+
+    school.fails("admin", NotenCall::set_grading_scale(GradingScale { min: 0, max: 100, step: 1 }), NotenError::InvalidScale);
+    school.call("admin", NotenCall::set_grading_scale(GradingScale { min: 1, max: 100, step: 1 }));
+}
+
+#[test]
+fn exam_summary_of_grades() {
+    let mut school = Fixture::builder().student("bob", "2a").student("dan", "2a").student("eva", "2a").student("tim", "2a").build();
+    school.call("admin", NotenCall::register_exam(&Exam {
+        subject: "maths".to_string(),
+        class: "2a".to_string(),
+        year: fixture::YEAR,
+        date: NotenContract::get_now_u64(),
+        exam_type: "exam".to_string(),
+    }));
+    let scale = GradingScale::default();
+    let summary = |school: &Fixture| {
+        let (sum, histogram) = school.contract.exam_stats(0).unwrap();
+        ExamStats { sum, histogram }.summary(&scale)
+    };
+    assert_eq!((summary(&school).count, summary(&school).mean), (0, None));
+
+    for (student, grade) in [("bob", 40), ("dan", 45), ("eva", 60)] {
+        school.call("admin", NotenCall::grade_exam(school.key(student), 0, grade));
+    }
+    let three = summary(&school);
+    assert_eq!((three.count, three.mean, three.median, three.min, three.max), (3, Some(48), Some(45), Some(40), Some(60)));
+
+    // With an even count the median lies between the two middle grades and is rounded like averages.
+    school.call("admin", NotenCall::grade_exam(school.key("tim"), 0, 50));
+    let four = summary(&school);
+    assert_eq!((four.count, four.mean, four.median, four.min, four.max), (4, Some(49), Some(48), Some(40), Some(60)));
+}
+
+#[test]
+fn grades_imported_from_csv() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    let roster = format!(
+        "student number,key\n1001,{}\n1002,{}\n",
+        Key::Account(bob).to_formatted_string(),
+        Key::Account(tim).to_formatted_string()
+    );
+    let students = import::Students::Roster(import::read_roster(&roster).unwrap());
+
+    let errors = import::read_grades(
+        "student number;subject;type;grade;comment\n1001;maths;exam;4,2;\n1003;maths;exam;5;\n1002;;exam;5;\n",
+        &students,
+        &GradingScale::default(),
+    )
+    .unwrap_err();
+    assert_eq!(errors.iter().map(|error| error.line).collect::<Vec<_>>(), vec![2, 3, 4]);
+
+    // Without a header the first row would be skipped, so it is reported instead.
+    let errors = import::read_grades("1001,maths,exam,4.5,\n1002,maths,exam,5,\n", &students, &GradingScale::default()).unwrap_err();
+    assert_eq!(errors.iter().map(|error| error.line).collect::<Vec<_>>(), vec![1]);
+    assert!(import::read_grades("", &students, &GradingScale::default()).is_err());
+    let errors = import::read_roster(&format!("1001,{}\n", Key::Account(bob).to_formatted_string())).unwrap_err();
+    assert_eq!(errors[0].line, 1);
+    assert!(import::read_grades("Student Number,Subject,Type,Grade\n1001,maths,exam,4.5\n", &students, &GradingScale::default()).is_ok());
+
+    let rows = import::read_grades(
+        "student number,subject,type,grade,comment\n1001,maths,exam,4.5,\"good, keep going\"\n1002,french,project,5,\n",
+        &students,
+        &GradingScale::default(),
+    )
+    .unwrap();
+    assert_eq!(rows[0].comment, "good, keep going");
+    for row in &rows {
+        contract.call(&ali, row.call(2026), NotenContract::get_now_u64());
+    }
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    assert_eq!(contract.token_meta(token_id).unwrap()["grade"], "45");
+    let token_id = contract.get_token_by_index(tim, U256::zero()).unwrap();
+    assert_eq!(contract.token_meta(token_id).unwrap()["subject"], "french");
+}
+
+#[test]
+fn cli_import_resumes_from_line() {
+    let dir = env::temp_dir().join(format!("noten-import-test-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let secret_key = dir.join("secret_key.pem");
+    NotenContract::create_secret_key().to_file(&secret_key).unwrap();
+    let grades = dir.join("grades.csv");
+    fs::write(&grades, "student number,subject,type,grade,comment\n1001,maths,exam,4.5,\n1002,maths,exam,5,\n1003,maths,exam,4,\n").unwrap();
+    let output_dir = dir.join("deploys");
+
+    let args: [&str; 18] = [
+        "import-grades",
+        "--secret-key",
+        &secret_key.to_string_lossy(),
+        "--chain-name",
+        "casper-test",
+        "--package",
+        &hex::encode([7u8; 32]),
+        "--year",
+        "2026",
+        "--salt",
+        "zuger-salt",
+        "--output-dir",
+        &output_dir.to_string_lossy(),
+        "--from-line",
+        "3",
+        "--scale",
+        "10,60,5",
+        &grades.to_string_lossy(),
+    ];
+    // Rows can't be checked against a guessed scale.
+    let without_scale = args.iter().enumerate().filter(|&(position, _)| position != 15 && position != 16);
+    let error = noten_cli::run(without_scale.map(|(_, arg)| arg.to_string()), &mut Vec::new()).unwrap_err();
+    assert_eq!(error, "missing --scale");
+    let mut out = Vec::new();
+    noten_cli::run(args.iter().map(|arg| arg.to_string()), &mut out).unwrap();
+
+    let out = String::from_utf8(out).unwrap();
+    assert!(out.ends_with("2 deploys were written, for lines 3 to 4\n"), "{}", out);
+    let mut written = fs::read_dir(&output_dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    written.sort();
+    assert_eq!(written, ["grade-3.json", "grade-4.json"]);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn transcript_exported() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 40);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 55);
+    contract.grade(&ali, bob, "french".to_string(), 2026, "project".to_string(), 45);
+    let revoked = contract.get_token_by_index(bob, U256::one()).unwrap();
+    contract.revoke_grade(&admin, revoked);
+
+    let transcript = Transcript::read(&contract, Key::Account(bob)).unwrap();
+    assert_eq!(transcript.school, "Zuger Kantonal");
+    assert_eq!(transcript.grades.len(), 3);
+    assert_eq!(transcript.grades.iter().filter(|grade| grade.revoked).count(), 1);
+    let averages: Vec<(&str, Option<u32>)> = transcript.averages.iter().map(|average| (average.subject.as_str(), average.average)).collect();
+    assert_eq!(averages, vec![("french", Some(45)), ("maths", Some(40))]);
+
+    let json: serde_json::Value = serde_json::from_str(&transcript.to_json()).unwrap();
+    assert_eq!(json["meta"]["kanton"], "ZG");
+    assert!(transcript.to_csv().contains("2026,maths,average,4.0,,,\n"));
+    let pdf = transcript.to_pdf();
+    assert!(pdf.starts_with(b"%PDF-1.4") && pdf.ends_with(b"%%EOF\n"));
+}
+
+#[test]
+fn grade_credential_verified() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, _dan) = contract.accounts;
+    let package = contract.noten.1;
+
+    contract.add_teacher(&admin, ali);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+
+    let vc = credential::grade_credential(&contract, package, token_id).unwrap();
+    assert_eq!(vc["credentialSubject"]["id"], format!("urn:casper:{}", Key::Account(bob).to_formatted_string()));
+    assert_eq!(vc["issuer"]["name"], "Zuger Kantonal");
+    assert_eq!(credential::verify(&contract, package, &vc), Ok(()));
+
+    let mut forged = vc.clone();
+    forged["credentialSubject"]["grade"]["grade"] = 60.into();
+    assert_eq!(credential::verify(&contract, package, &forged), Err(CredentialError::Mismatch));
+    // Another school's package can't be vouched for by this school's state.
+    let (_, other) = NotenContract::deploy_nft(&mut contract.builder, &ali);
+    let claimed = credential::grade_credential(&contract, other, token_id).unwrap();
+    assert_eq!(credential::verify(&contract, other, &claimed), Err(CredentialError::WrongIssuer));
+
+    contract.update_grade(&ali, bob, 40, token_id);
+    assert_eq!(credential::verify(&contract, package, &vc), Err(CredentialError::Mismatch));
+    contract.revoke_grade(&admin, token_id);
+    assert_eq!(credential::verify(&contract, package, &vc), Err(CredentialError::Revoked));
+}
+
+#[test]
+fn diploma_credential_verified() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, dan) = contract.accounts;
+    let package = contract.noten.1;
+
+    contract.add_teacher(&admin, ali);
+    contract.set_student_class(&admin, dan, "4b".to_string());
+    contract.grade(&ali, dan, "maths".to_string(), 2026, "exam".to_string(), 45);
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 10);
+
+    let vc = credential::diploma_credential(&contract, package, Key::Account(dan)).unwrap();
+    assert_eq!(vc["credentialSubject"]["diploma"]["year"], 2026);
+    assert_eq!(vc["credentialSubject"]["diploma"]["finalAverages"][0]["average"], 45);
+    assert_eq!(credential::verify(&contract, package, &vc), Ok(()));
+    assert_eq!(credential::diploma_credential(&contract, package, Key::Account(bob)), Err(CredentialError::NotGraduated));
+}
+
+#[test]
+fn staff_changes_logged() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, ali, bob, _dan) = contract.accounts;
+
+    contract.add_teacher(&admin, ali);
+    contract.appoint_teacher(&admin, tim, 0, u64::MAX, vec!["maths".to_string()], vec![]);
+    contract.set_student_class(&admin, bob, "2a".to_string());
+    contract.remove_teacher(&admin, ali);
+
+    assert_eq!(contract.named_key::<u64>("staff_event_count"), 5);
+    let events: Vec<(String, String)> = (0..5)
+        .map(|index| {
+            let event = contract.staff_event(index).unwrap();
+            (event["event"].clone(), event["account"].clone())
+        })
+        .collect();
+    let account = |account| Key::Account(account).to_formatted_string();
+    assert_eq!(events, vec![
+        ("teacher_added".to_string(), account(admin)),
+        ("teacher_added".to_string(), account(ali)),
+        ("teacher_appointed".to_string(), account(tim)),
+        ("class_set".to_string(), account(bob)),
+        ("teacher_removed".to_string(), account(ali)),
+    ]);
+}
+
+#[test]
+fn indexer_mirrors_state() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, _tim, ali, bob, dan) = contract.accounts;
+    let mut indexer = Indexer::open_in_memory().unwrap();
+
+    contract.add_teacher(&admin, ali);
+    contract.set_student_class(&admin, bob, "2a".to_string());
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 40);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 50);
+    contract.grade(&ali, dan, "french".to_string(), 2026, "project".to_string(), 55);
+    assert_eq!(indexer.sync(&contract).unwrap(), 6);
+    assert_eq!(indexer.check(&contract).unwrap(), vec![]);
+
+    let bob_key = Key::Account(bob).to_formatted_string();
+    let average: u32 = indexer
+        .connection()
+        .query_row("SELECT average FROM averages WHERE student = ?1 AND subject = 'maths'", [&bob_key], |row| row.get(0))
+        .unwrap();
+    assert_eq!(average, 45);
+
+    let token_id = contract.get_token_by_index(bob, U256::zero()).unwrap();
+    contract.revoke_grade(&admin, token_id);
+    contract.remove_teacher(&admin, ali);
+    let mismatches = indexer.check(&contract).unwrap();
+    assert_eq!(mismatches.iter().map(|mismatch| mismatch.table).collect::<Vec<_>>(), vec!["grades", "teachers", "averages"]);
+
+    assert_eq!(indexer.sync(&contract).unwrap(), 2);
+    assert_eq!(indexer.check(&contract).unwrap(), vec![]);
+    let revoked: bool = indexer
+        .connection()
+        .query_row("SELECT revoked FROM grades WHERE token_id = ?1", [token_id.to_string()], |row| row.get(0))
+        .unwrap();
+    assert!(revoked);
+}
+
+#[test]
+fn indexer_follows_rollover_and_new_teachers() {
+    let mut contract = NotenContract::deploy_noten();
+    let (admin, _biff, tim, ali, bob, dan) = contract.accounts;
+    let mut indexer = Indexer::open_in_memory().unwrap();
+
+    contract.add_teacher(&admin, ali);
+    contract.set_student_class(&admin, bob, "2a".to_string());
+    contract.set_student_class(&admin, dan, "4b".to_string());
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 30);
+    contract.grade(&ali, bob, "maths".to_string(), 2026, "exam".to_string(), 45);
+    indexer.sync(&contract).unwrap();
+
+    // The rollover logs each promotion and graduation.
+    contract.rollover_year(&admin, 2026, "archive-2026".to_string(), 10);
+    let count = contract.named_key::<u64>("staff_event_count");
+    let logged: Vec<(String, String)> = (count - 2..count)
+        .map(|index| {
+            let event = contract.staff_event(index).unwrap();
+            (event["event"].clone(), event["account"].clone())
+        })
+        .collect();
+    let bob_key = Key::Account(bob).to_formatted_string();
+    assert_eq!(logged, vec![
+        ("class_set".to_string(), bob_key.clone()),
+        ("graduated".to_string(), Key::Account(dan).to_formatted_string()),
+    ]);
+    let mismatches = indexer.check(&contract).unwrap();
+    assert_eq!(mismatches.iter().map(|mismatch| mismatch.table).collect::<Vec<_>>(), vec!["students", "students", "averages"]);
+
+    assert_eq!(indexer.sync(&contract).unwrap(), 2);
+    assert_eq!(indexer.check(&contract).unwrap(), vec![]);
+    let (class, final_average): (String, u32) = indexer
+        .connection()
+        .query_row(
+            "SELECT class, final_average FROM students JOIN averages ON student = account WHERE account = ?1",
+            [&bob_key],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .unwrap();
+    assert_eq!((class.as_str(), final_average), ("3a", 38));
+
+    // A teacher added since the last sync is reported missing from the mirror.
+    contract.add_teacher(&admin, tim);
+    let mismatches = indexer.check(&contract).unwrap();
+    assert_eq!(mismatches.len(), 1);
+    assert_eq!((mismatches[0].table, mismatches[0].key.clone()), ("teachers", Key::Account(tim).to_formatted_string()));
+    assert_eq!(mismatches[0].mirror, None);
+}
+
+/// Signs a deploy with `noten-cli make-deploy <args>` as the owner of `secret_key`, sends it to the
+/// devnode behind `state` and returns its hash.
+fn cli_deploy(state: &RpcState, secret_key: &Path, package: ContractPackageHash, args: &[&str]) -> String {
+    let output = secret_key.with_file_name(format!("deploy-{}.json", hex::encode(rand::random::<[u8; 8]>())));
+    let command: [&str; 9] = [
+        "make-deploy",
+        "--secret-key",
+        &secret_key.to_string_lossy(),
+        "--chain-name",
+        "noten-devnode",
+        "--package",
+        &hex::encode(package.value()),
+        "--output",
+        &output.to_string_lossy(),
+    ];
+    let command = command.iter().chain(args).map(|arg| arg.to_string());
+    noten_cli::run(command, &mut io::sink()).unwrap();
+    let deploy: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    state.request("account_put_deploy", json!({ "deploy": deploy })).unwrap();
+    deploy["hash"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn devnode_serves_client_and_indexer() {
+    let dir = env::temp_dir().join(format!("noten-devnode-test-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let key_file = |name: &str| {
+        let secret_key = NotenContract::create_secret_key();
+        let path = dir.join(format!("{}.pem", name));
+        secret_key.to_file(&path).unwrap();
+        (PublicKey::from(&secret_key), path)
+    };
+    let (admin, admin_key) = key_file("admin");
+    let (ali, ali_key) = key_file("ali");
+    let (bob, bob_key) = key_file("bob");
+    let bob_account = Key::Account(bob.to_account_hash()).to_formatted_string();
+
+    // The builder can't leave the thread it was made on, so the node is started there.
+    let (sender, receiver) = mpsc::channel();
+    let node_admin = admin.clone();
+    thread::spawn(move || {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let meta = btreemap! { "school".to_string() => "Zuger Kantonal".to_string() };
+        let mut node = DevNode::start(&wasm_path(Path::new("noten.wasm")), &node_admin, meta).unwrap();
+        sender.send((server.server_addr().port(), node.contract(), node.package())).unwrap();
+        node.serve(&server);
+    });
+    let (port, contract, package) = receiver.recv().unwrap();
+    let url = format!("http://127.0.0.1:{}/rpc", port);
+    let contract = Key::Hash(contract.value());
+
+    let state = RpcState::connect(&url, contract).unwrap();
+    let teacher = format!("teacher={}", Key::Account(ali.to_account_hash()).to_formatted_string());
+    cli_deploy(&state, &admin_key, package, &["add_teacher", &teacher]);
+    let student = format!("student={}", bob_account);
+    let graded = cli_deploy(&state, &ali_key, package, &["grade", &student, "subject=maths", "year=2026", "type=exam", "grade=45"]);
+    let refused = cli_deploy(&state, &bob_key, package, &["grade", &student, "subject=maths", "year=2026", "type=exam", "grade=60"]);
+
+    let result = |hash: &str| state.request("info_get_deploy", json!({ "deploy_hash": hash })).unwrap()["execution_results"][0]["result"].clone();
+    assert!(result(&graded)["Success"].is_object());
+    assert!(result(&refused)["Failure"]["error_message"].as_str().unwrap().contains("NotTeacher"));
+
+    // Reads stay at the state root hash they connected at, from before the deploys.
+    assert_eq!(Indexer::open_in_memory().unwrap().sync(&state).unwrap(), 1);
+    let state = RpcState::connect(&url, contract).unwrap();
+    let mut indexer = Indexer::open_in_memory().unwrap();
+    assert_eq!(indexer.sync(&state).unwrap(), 3);
+    assert_eq!(indexer.check(&state).unwrap(), vec![]);
+    let grade: u32 = indexer.connection().query_row("SELECT grade FROM grades", [], |row| row.get(0)).unwrap();
+    assert_eq!(grade, 45);
+
+    // The grade record as `casper-client get-dictionary-item` prints it, decoded by the CLI.
+    let owned = keys::owned_token_key(&Key::Account(bob.to_account_hash()), U256::zero());
+    let token_id: TokenId = read_dictionary_item(&state, keys::OWNED_TOKENS_BY_INDEX, &owned)
+        .unwrap()
+        .unwrap();
+    let item = state
+        .request(
+            "state_get_dictionary_item",
+            json!({
+                "state_root_hash": state.state_root_hash(),
+                "dictionary_identifier": {
+                    "ContractNamedKey": {
+                        "key": contract.to_formatted_string(),
+                        "dictionary_name": keys::GRADE_RECORDS,
+                        "dictionary_item_key": keys::token_key(token_id),
+                    }
+                },
+            }),
+        )
+        .unwrap();
+    let query = dir.join("grade-record.json").to_string_lossy().into_owned();
+    fs::write(&query, json!({ "result": item }).to_string()).unwrap();
+    let mut table = Vec::new();
+    noten_cli::run(["decode", "grade-record", query.as_str()].iter().map(|arg| arg.to_string()), &mut table).unwrap();
+    let table = String::from_utf8(table).unwrap();
+    assert!(table.lines().any(|line| line.split_whitespace().collect::<Vec<_>>() == ["grade", "45"]), "{}", table);
+    assert!(table.contains(&bob_account), "{}", table);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,203 @@
+//! Random sequences of staff changes and grading against the contract, checked against a model
+//! of who may do what and the state it has to end up in.
+
+use std::collections::BTreeSet;
+
+use casper_types::U256;
+use cep47::TokenId;
+use noten_client::total;
+use noten_client::transcript::Transcript;
+use noten_client::{NotenCall, NotenError};
+use proptest::prelude::*;
+use proptest::sample::Index;
+
+use crate::fixture::{Fixture, YEAR};
+
+const CALLERS: [&str; 4] = ["admin", "ali", "tim", "bob"];
+/// Everyone whose teaching rights can change; `admin` stays so the school can't lock itself out.
+const STAFF: [&str; 3] = ["ali", "tim", "bob"];
+const STUDENTS: [&str; 2] = ["bob", "dan"];
+const SUBJECTS: [&str; 2] = ["maths", "french"];
+
+#[derive(Clone, Debug)]
+enum Step {
+    AddTeacher { caller: usize, teacher: usize },
+    RemoveTeacher { caller: usize, teacher: usize },
+    Grade { caller: usize, student: usize, subject: usize, grade: u32 },
+    /// Changes an earlier grade, picked among those given so far.
+    UpdateGrade { caller: usize, grade: Index, value: u32 },
+    RevokeGrade { caller: usize, grade: Index },
+}
+
+fn grade() -> impl Strategy<Value = u32> {
+    // The steps of the default grading scale, 1 to 6 in halves.
+    (2u32..=12).prop_map(|step| step * 5)
+}
+
+fn step() -> impl Strategy<Value = Step> {
+    let caller = 0..CALLERS.len();
+    prop_oneof![
+        1 => (caller.clone(), 0..STAFF.len()).prop_map(|(caller, teacher)| Step::AddTeacher { caller, teacher }),
+        1 => (caller.clone(), 0..STAFF.len()).prop_map(|(caller, teacher)| Step::RemoveTeacher { caller, teacher }),
+        3 => (caller.clone(), 0..STUDENTS.len(), 0..SUBJECTS.len(), grade())
+            .prop_map(|(caller, student, subject, grade)| Step::Grade { caller, student, subject, grade }),
+        1 => (caller.clone(), any::<Index>(), grade()).prop_map(|(caller, grade, value)| Step::UpdateGrade { caller, grade, value }),
+        1 => (caller, any::<Index>()).prop_map(|(caller, grade)| Step::RevokeGrade { caller, grade }),
+    ]
+}
+
+struct ModelGrade {
+    token_id: TokenId,
+    student: &'static str,
+    subject: &'static str,
+    grade: u32,
+    revoked: bool,
+}
+
+/// What the contract should hold after the steps so far.
+struct Model {
+    teachers: BTreeSet<&'static str>,
+    grades: Vec<ModelGrade>,
+}
+
+impl Model {
+    /// Whether `caller` may change staff or grades, or change `grade` if given, and the error the
+    /// contract has to revert with if not.
+    fn check(&self, caller: &str, grade: Option<&ModelGrade>) -> Result<(), NotenError> {
+        if !self.teachers.contains(caller) {
+            return Err(NotenError::NotTeacher);
+        }
+        match grade {
+            Some(grade) if grade.revoked => Err(NotenError::GradeRevoked),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn run(school: &mut Fixture, model: &mut Model, step: &Step) {
+    match *step {
+        Step::AddTeacher { caller, teacher } => {
+            let (caller, teacher) = (CALLERS[caller], STAFF[teacher]);
+            let call = NotenCall::add_teacher(school.key(teacher));
+            match model.check(caller, None) {
+                Ok(()) => {
+                    school.call(caller, call);
+                    model.teachers.insert(teacher);
+                }
+                Err(error) => school.fails(caller, call, error),
+            }
+        }
+        Step::RemoveTeacher { caller, teacher } => {
+            let (caller, teacher) = (CALLERS[caller], STAFF[teacher]);
+            let call = NotenCall::remove_teacher(school.key(teacher));
+            match model.check(caller, None) {
+                Ok(()) => {
+                    school.call(caller, call);
+                    model.teachers.remove(teacher);
+                }
+                Err(error) => school.fails(caller, call, error),
+            }
+        }
+        Step::Grade { caller, student, subject, grade } => {
+            let (caller, student, subject) = (CALLERS[caller], STUDENTS[student], SUBJECTS[subject]);
+            match model.check(caller, None) {
+                Ok(()) => {
+                    let token_id = school.grade(caller, student, subject, grade);
+                    model.grades.push(ModelGrade { token_id, student, subject, grade, revoked: false });
+                }
+                Err(error) => school.fails(caller, NotenCall::grade(school.key(student), subject, YEAR, "exam", grade), error),
+            }
+        }
+        Step::UpdateGrade { caller, ref grade, value } => {
+            if model.grades.is_empty() {
+                return;
+            }
+            let (caller, position) = (CALLERS[caller], grade.index(model.grades.len()));
+            let target = &model.grades[position];
+            let call = NotenCall::update_grade(school.key(target.student), value, target.token_id);
+            match model.check(caller, Some(target)) {
+                Ok(()) => {
+                    school.call(caller, call);
+                    model.grades[position].grade = value;
+                }
+                Err(error) => school.fails(caller, call, error),
+            }
+        }
+        Step::RevokeGrade { caller, ref grade } => {
+            if model.grades.is_empty() {
+                return;
+            }
+            let (caller, position) = (CALLERS[caller], grade.index(model.grades.len()));
+            let target = &model.grades[position];
+            let call = NotenCall::revoke_grade(target.token_id);
+            match model.check(caller, Some(target)) {
+                Ok(()) => {
+                    school.call(caller, call);
+                    model.grades[position].revoked = true;
+                }
+                Err(error) => school.fails(caller, call, error),
+            }
+        }
+    }
+}
+
+/// The average of grades, rounded like the contract rounds them.
+fn average(grades: &[u32]) -> Option<u32> {
+    total::average((grades.iter().map(|&grade| grade as u64).sum(), grades.len() as u32))
+}
+
+proptest! {
+    // Every case deploys a contract, which takes a while, so there are fewer than the default 256.
+    #![proptest_config(ProptestConfig { cases: 64, ..ProptestConfig::default() })]
+
+    #[test]
+    fn grading_invariants(steps in prop::collection::vec(step(), 1..16)) {
+        let mut school = Fixture::builder().account("ali").account("tim").student("bob", "2a").student("dan", "2b").build();
+        let mut model = Model { teachers: BTreeSet::from(["admin"]), grades: Vec::new() };
+
+        // Only authorised callers succeed: `run` asserts each step's outcome.
+        for step in &steps {
+            run(&mut school, &mut model, step);
+        }
+
+        // The global grade index lists every grade given, revoked or not, in the order given.
+        let grade_count = school.contract.named_key::<u64>("grade_count");
+        let index: Vec<TokenId> = (0..grade_count).filter_map(|position| school.contract.indexed_grade(position)).collect();
+        let issued: Vec<TokenId> = model.grades.iter().map(|grade| grade.token_id).collect();
+        prop_assert_eq!(&index, &issued);
+
+        for student in STUDENTS {
+            let key = school.key(student);
+            let tokens = school.tokens(student);
+            let issued: Vec<TokenId> = model.grades.iter().filter(|grade| grade.student == student).map(|grade| grade.token_id).collect();
+            prop_assert_eq!(&tokens, &issued);
+            prop_assert_eq!(school.contract.balance_of(key), U256::from(tokens.len()));
+            prop_assert_eq!(index.iter().filter(|token_id| tokens.contains(token_id)).count(), tokens.len());
+
+            let transcript = Transcript::read(&school.contract, key).unwrap();
+            for subject in SUBJECTS {
+                // The grades that count, as recorded on chain, and as the model expects them.
+                let recorded: Vec<u32> = tokens
+                    .iter()
+                    .filter(|&&token_id| !school.contract.is_revoked(token_id))
+                    .filter_map(|&token_id| school.contract.grade_record(token_id))
+                    .filter(|record| record.subject == subject)
+                    .map(|record| record.grade)
+                    .collect();
+                let expected: Vec<u32> = model
+                    .grades
+                    .iter()
+                    .filter(|grade| grade.student == student && grade.subject == subject && !grade.revoked)
+                    .map(|grade| grade.grade)
+                    .collect();
+                prop_assert_eq!(&recorded, &expected);
+
+                let stored = transcript.averages.iter().find(|stored| stored.subject == subject).and_then(|stored| stored.average);
+                prop_assert_eq!(stored, average(&recorded));
+            }
+            for grade in model.grades.iter().filter(|grade| grade.student == student) {
+                prop_assert_eq!(school.contract.is_revoked(grade.token_id), grade.revoked);
+            }
+        }
+    }
+}